@@ -5,7 +5,7 @@ fn main() {
     //load the game
     let bytes = std::fs::read("roms/nestest.nes").unwrap();
     let rom = Rom::new(&bytes).unwrap();
-    let mut cpu = CPU::new(rom);
+    let mut cpu = CPU::new(rom).unwrap();
     cpu.program_counter = 0xC000;
 
     let expected_logs = std::fs::read_to_string("nestest.log").unwrap();
@@ -1,7 +1,7 @@
 use std::{thread, time::Duration};
 
 use nes_emulator::Rom;
-use nes_emulator::{Mem, CPU};
+use nes_emulator::{Instruction, Mem, CPU};
 use rand::Rng;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
@@ -31,14 +31,16 @@ fn main() {
     //load the game
     let bytes = std::fs::read("roms/snake.nes").unwrap();
     let rom = Rom::new(&bytes).unwrap();
-    let mut cpu = CPU::new(rom);
+    let mut cpu = CPU::new(rom).unwrap();
 
     let mut screen_state = [0; 32 * 3 * 32];
     let mut rng = rand::rng();
 
-    // run the game cycle
-    cpu.run_with_callback(move |cpu| {
-        handle_user_input(cpu, &mut event_pump);
+    // run the game cycle, it ends with a BRK
+    cpu.run_until(move |cpu, step| {
+        if handle_user_input(cpu, &mut event_pump) {
+            return true;
+        }
 
         cpu.mem_write(0xfe, rng.random_range(1..16));
 
@@ -51,6 +53,8 @@ fn main() {
         }
 
         thread::sleep(Duration::new(0, 70_000));
+
+        step.jammed || matches!(step.instruction, Some(Instruction::BRK(_)))
     });
 }
 
@@ -68,11 +72,11 @@ fn color(byte: u8) -> Color {
     }
 }
 
-fn read_screen_state(cpu: &mut CPU, frame: &mut [u8; 32 * 3 * 32]) -> bool {
+fn read_screen_state(cpu: &CPU, frame: &mut [u8; 32 * 3 * 32]) -> bool {
     let mut frame_idx = 0;
     let mut update = false;
     for i in 0x0200..0x600 {
-        let color_idx = cpu.mem_read(i as u16);
+        let color_idx = cpu.peek(i as u16);
         let (b1, b2, b3) = color(color_idx).rgb();
         if frame[frame_idx] != b1 || frame[frame_idx + 1] != b2 || frame[frame_idx + 2] != b3 {
             frame[frame_idx] = b1;
@@ -85,14 +89,15 @@ fn read_screen_state(cpu: &mut CPU, frame: &mut [u8; 32 * 3 * 32]) -> bool {
     update
 }
 
-fn handle_user_input(cpu: &mut CPU, event_pump: &mut EventPump) {
+/// Returns whether the player asked to quit
+fn handle_user_input(cpu: &mut CPU, event_pump: &mut EventPump) -> bool {
     for event in event_pump.poll_iter() {
         match event {
             Event::Quit { .. }
             | Event::KeyDown {
                 keycode: Some(Keycode::Escape),
                 ..
-            } => std::process::exit(0),
+            } => return true,
             Event::KeyDown {
                 keycode: Some(Keycode::W),
                 ..
@@ -120,4 +125,6 @@ fn handle_user_input(cpu: &mut CPU, event_pump: &mut EventPump) {
             _ => { /* do nothing */ }
         }
     }
+
+    false
 }
@@ -58,7 +58,7 @@ pub fn instruction(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     quote! {
         impl OpCode for #name {
             fn fetch(cpu: &mut CPU) -> Instruction {
-                match cpu.mem_read(cpu.program_counter) {
+                match cpu.fetch_opcode() {
                     #(#fetch,)*
                 }
             }
@@ -0,0 +1,156 @@
+use crate::Timing;
+
+use super::{Filter, Resampler};
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+/// CPU clock rate of the region in Hz, the rate at which the mixer output is sampled
+fn clock_rate(timing: Timing) -> f64 {
+    match timing {
+        Timing::NTSC | Timing::MultiRegion => 236.25e6 / 11.0 / 12.0,
+        Timing::PAL => 26.6017125e6 / 16.0,
+        Timing::Dendy => 26.6017125e6 / 15.0,
+    }
+}
+
+/// Nonlinear mixing of the channel levels, as done by the resistor networks on the console.
+/// Pulses are 0-15, the triangle and noise 0-15 and the DMC 0-127; the output is 0.0 to ~1.0
+pub fn mix(pulse_1: u8, pulse_2: u8, triangle: u8, noise: u8, dmc: u8) -> f32 {
+    let pulse = (pulse_1 + pulse_2) as f32;
+    let pulse_out = if pulse == 0.0 {
+        0.0
+    } else {
+        95.88 / (8128.0 / pulse + 100.0)
+    };
+
+    let tnd = triangle as f32 / 8227.0 + noise as f32 / 12241.0 + dmc as f32 / 22638.0;
+    let tnd_out = if tnd == 0.0 {
+        0.0
+    } else {
+        159.79 / (1.0 / tnd + 100.0)
+    };
+
+    pulse_out + tnd_out
+}
+
+/// Host audio stream: the mixer output resampled to `sample_rate`, then run through
+/// the high-pass filters at 90 Hz and 440 Hz and the low-pass filter at 14 kHz
+/// of the console's output stage.
+#[derive(Debug, Clone)]
+pub struct AudioOutput {
+    clock_rate: f64,
+    sample_rate: u32,
+    resampler: Resampler,
+    filters: [Filter; 3],
+}
+
+impl AudioOutput {
+    pub fn new(timing: Timing, sample_rate: u32) -> Self {
+        Self::with_clock_rate(clock_rate(timing), sample_rate)
+    }
+
+    fn with_clock_rate(clock_rate: f64, sample_rate: u32) -> Self {
+        Self {
+            clock_rate,
+            sample_rate,
+            resampler: Resampler::new(clock_rate, sample_rate),
+            filters: [
+                Filter::high_pass(90.0, sample_rate),
+                Filter::high_pass(440.0, sample_rate),
+                Filter::low_pass(14000.0, sample_rate),
+            ],
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Restart the stream at another host rate, dropping the samples not read yet
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        *self = Self::with_clock_rate(self.clock_rate, sample_rate);
+    }
+
+    /// Mixer output on the current CPU cycle
+    pub fn clock(&mut self, level: f32) {
+        self.resampler.clock(level);
+    }
+
+    /// Samples produced since the last read, between -1.0 and 1.0
+    pub fn samples(&mut self) -> Vec<f32> {
+        let mut samples = Vec::new();
+        self.resampler.read(&mut samples);
+
+        for sample in &mut samples {
+            *sample = self
+                .filters
+                .iter_mut()
+                .fold(*sample, |sample, filter| filter.process(sample));
+        }
+        samples
+    }
+
+    /// Samples produced since the last read, as signed 16-bit PCM
+    pub fn samples_i16(&mut self) -> Vec<i16> {
+        self.samples()
+            .into_iter()
+            .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(0, 0, 0, 0, 0, 0.0 ; "silent")]
+    #[test_case(15, 15, 0, 0, 0, 0.2585 ; "pulses")]
+    #[test_case(0, 0, 15, 15, 127, 0.7415 ; "tnd")]
+    #[test_case(15, 15, 15, 15, 127, 1.0 ; "full")]
+    fn mixer(pulse_1: u8, pulse_2: u8, triangle: u8, noise: u8, dmc: u8, output: f32) {
+        let level = mix(pulse_1, pulse_2, triangle, noise, dmc);
+        assert!((level - output).abs() < 1e-4, "{level}");
+    }
+
+    #[test_case(44100 ; "44_1_khz")]
+    #[test_case(48000 ; "48_khz")]
+    fn frame(sample_rate: u32) {
+        let mut audio = AudioOutput::new(Timing::NTSC, sample_rate);
+
+        // A 60 Hz frame is 29780.5 CPU cycles
+        for cycle in 0..29780 {
+            audio.clock(if cycle % 4000 < 2000 { 0.5 } else { 0.0 });
+        }
+        let samples = audio.samples();
+
+        assert!(samples.len().abs_diff(sample_rate as usize / 60) <= 2);
+        assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+        assert!(samples.iter().any(|sample| sample.abs() > 0.1));
+    }
+
+    #[test]
+    fn set_sample_rate() {
+        let mut audio = AudioOutput::new(Timing::PAL, DEFAULT_SAMPLE_RATE);
+        audio.set_sample_rate(48000);
+
+        for _ in 0..33247 {
+            audio.clock(0.0);
+        }
+        assert_eq!(audio.sample_rate(), 48000);
+        assert_eq!(audio.samples().len(), 48000 / 50 - 1);
+    }
+
+    #[test]
+    fn samples_i16() {
+        let mut audio = AudioOutput::new(Timing::NTSC, DEFAULT_SAMPLE_RATE);
+        for _ in 0..1000 {
+            audio.clock(1.0);
+        }
+
+        let samples = audio.samples_i16();
+        assert!(samples.iter().any(|&sample| sample > i16::MAX / 2));
+        assert!(audio.samples_i16().is_empty());
+    }
+}
@@ -0,0 +1,296 @@
+use crate::Timing;
+
+/// Timer periods selected by $4010, in CPU cycles
+const NTSC_RATES: [u16; 16] = [
+    428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54,
+];
+const PAL_RATES: [u16; 16] = [
+    398, 354, 316, 298, 276, 236, 210, 198, 176, 148, 132, 118, 98, 78, 66, 50,
+];
+
+/// Delta modulation channel, playing 1-bit delta encoded samples read from CPU memory.
+///
+/// The memory reader refills the sample buffer through DMA whenever it is empty and bytes
+/// remain, halting the CPU; `dma_address` tells the bus when a fetch is due.
+#[derive(Debug, Clone)]
+pub struct Dmc {
+    rates: &'static [u16; 16],
+    irq_enabled: bool,
+    looping: bool,
+    timer_period: u16,
+    timer: u16,
+    /// 7-bit output level, moved up or down by 2 for every bit of the sample
+    level: u8,
+    shift_register: u8,
+    bits_remaining: u8,
+    /// Set when the output cycle started with an empty sample buffer, holding the level
+    silence: bool,
+    sample_buffer: Option<u8>,
+    sample_address: u16,
+    sample_length: u16,
+    current_address: u16,
+    bytes_remaining: u16,
+    irq: bool,
+}
+
+impl Dmc {
+    pub fn new(timing: Timing) -> Self {
+        let rates = match timing {
+            Timing::PAL => &PAL_RATES,
+            Timing::NTSC | Timing::MultiRegion | Timing::Dendy => &NTSC_RATES,
+        };
+
+        Self {
+            rates,
+            irq_enabled: false,
+            looping: false,
+            timer_period: rates[0],
+            timer: 0,
+            level: 0,
+            shift_register: 0,
+            bits_remaining: 8,
+            silence: true,
+            sample_buffer: None,
+            sample_address: 0xC000,
+            sample_length: 1,
+            current_address: 0xC000,
+            bytes_remaining: 0,
+            irq: false,
+        }
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// IL-- RRRR
+    /// ||   ||||
+    /// ||   ++++- Index in the rate table
+    /// |+-------- Loop the sample
+    /// +--------- IRQ enabled, clearing it acknowledges the interrupt
+    pub fn write_control(&mut self, data: u8) {
+        self.irq_enabled = data & 0b1000_0000 != 0;
+        if !self.irq_enabled {
+            self.irq = false;
+        }
+        self.looping = data & 0b0100_0000 != 0;
+        self.timer_period = self.rates[(data & 0b1111) as usize];
+    }
+
+    /// $4011 write: -DDD DDDD, load the output level directly
+    pub fn write_direct_load(&mut self, data: u8) {
+        self.level = data & 0b0111_1111;
+    }
+
+    /// $4012 write: sample address = $C000 + A * 64
+    pub fn write_address(&mut self, data: u8) {
+        self.sample_address = 0xC000 | ((data as u16) << 6);
+    }
+
+    /// $4013 write: sample length = L * 16 + 1 bytes
+    pub fn write_length(&mut self, data: u8) {
+        self.sample_length = ((data as u16) << 4) | 1;
+    }
+
+    /// $4015 write: disabling stops the sample, enabling restarts it unless bytes remain.
+    /// Either way the interrupt is acknowledged
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.irq = false;
+        if !enabled {
+            self.bytes_remaining = 0;
+        } else if self.bytes_remaining == 0 {
+            self.restart();
+        }
+    }
+
+    fn restart(&mut self) {
+        self.current_address = self.sample_address;
+        self.bytes_remaining = self.sample_length;
+    }
+
+    /// Whether bytes of the sample remain to be fetched
+    pub fn is_active(&self) -> bool {
+        self.bytes_remaining > 0
+    }
+
+    pub fn irq(&self) -> bool {
+        self.irq
+    }
+
+    /// Period in CPU cycles
+    pub fn timer_period(&self) -> u16 {
+        self.timer_period
+    }
+
+    /// Address of the next sample byte when the buffer is empty and the DMA has to fetch it
+    pub fn dma_address(&self) -> Option<u16> {
+        match self.sample_buffer {
+            None if self.bytes_remaining > 0 => Some(self.current_address),
+            _ => None,
+        }
+    }
+
+    /// Fill the sample buffer with the byte fetched at `dma_address`.
+    /// The address wraps from $FFFF to $8000, and the last byte loops or raises the IRQ
+    pub fn load_sample(&mut self, data: u8) {
+        self.sample_buffer = Some(data);
+        self.current_address = match self.current_address {
+            0xFFFF => 0x8000,
+            addr => addr + 1,
+        };
+        self.bytes_remaining -= 1;
+
+        if self.bytes_remaining == 0 {
+            if self.looping {
+                self.restart();
+            } else if self.irq_enabled {
+                self.irq = true;
+            }
+        }
+    }
+
+    /// Clocked every CPU cycle, the rates being counted in CPU cycles
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+
+        self.timer = self.timer_period - 1;
+        if !self.silence {
+            if self.shift_register & 1 != 0 {
+                if self.level <= 125 {
+                    self.level += 2;
+                }
+            } else if self.level >= 2 {
+                self.level -= 2;
+            }
+        }
+        self.shift_register >>= 1;
+
+        self.bits_remaining -= 1;
+        if self.bits_remaining == 0 {
+            self.bits_remaining = 8;
+            match self.sample_buffer.take() {
+                Some(sample) => {
+                    self.silence = false;
+                    self.shift_register = sample;
+                }
+                None => self.silence = true,
+            }
+        }
+    }
+
+    /// Current level, 0 to 127
+    pub fn output(&self) -> u8 {
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    /// Run output cycles until the buffered sample has been played
+    fn play(dmc: &mut Dmc, bits: usize) {
+        for _ in 0..bits * dmc.timer_period() as usize {
+            dmc.clock_timer();
+        }
+    }
+
+    #[test]
+    fn registers() {
+        let mut dmc = Dmc::new(Timing::NTSC);
+        dmc.write_address(0xFF);
+        dmc.write_length(0xFF);
+        dmc.set_enabled(true);
+
+        assert_eq!(dmc.dma_address(), Some(0xFFC0));
+        assert_eq!(dmc.bytes_remaining, 0xFF1);
+
+        dmc.write_direct_load(0xFF);
+        assert_eq!(dmc.output(), 0x7F);
+    }
+
+    #[test_case(Timing::NTSC, 428, 54 ; "ntsc")]
+    #[test_case(Timing::PAL, 398, 50 ; "pal")]
+    fn rates(timing: Timing, slowest: u16, fastest: u16) {
+        let mut dmc = Dmc::new(timing);
+
+        dmc.write_control(0);
+        assert_eq!(dmc.timer_period(), slowest);
+        dmc.write_control(0x0F);
+        assert_eq!(dmc.timer_period(), fastest);
+    }
+
+    #[test]
+    fn address_wraps() {
+        let mut dmc = Dmc::new(Timing::NTSC);
+        dmc.write_address(0xFF);
+        dmc.write_length(0x04);
+        dmc.set_enabled(true);
+
+        for _ in 0..0x40 {
+            dmc.load_sample(0);
+            dmc.sample_buffer = None;
+        }
+        assert_eq!(dmc.dma_address(), Some(0x8000));
+    }
+
+    #[test]
+    fn delta() {
+        let mut dmc = Dmc::new(Timing::NTSC);
+        dmc.write_direct_load(64);
+        dmc.set_enabled(true);
+
+        // The first output cycle starts silent and picks up the buffered sample
+        dmc.load_sample(0b0000_0111);
+        play(&mut dmc, 8);
+        assert_eq!(dmc.output(), 64);
+        assert!(!dmc.silence);
+
+        play(&mut dmc, 3);
+        assert_eq!(dmc.output(), 70);
+        play(&mut dmc, 5);
+        assert_eq!(dmc.output(), 60);
+    }
+
+    #[test]
+    fn level_clamps() {
+        let mut dmc = Dmc::new(Timing::NTSC);
+        dmc.write_direct_load(126);
+        dmc.shift_register = 0xFF;
+        dmc.silence = false;
+
+        play(&mut dmc, 2);
+        assert_eq!(dmc.output(), 126);
+    }
+
+    #[test]
+    fn irq() {
+        let mut dmc = Dmc::new(Timing::NTSC);
+        dmc.write_control(0b1000_0000);
+        dmc.set_enabled(true);
+
+        dmc.load_sample(0);
+        assert!(dmc.irq());
+        assert!(!dmc.is_active());
+        assert_eq!(dmc.dma_address(), None);
+
+        dmc.write_control(0);
+        assert!(!dmc.irq());
+    }
+
+    #[test]
+    fn looping() {
+        let mut dmc = Dmc::new(Timing::NTSC);
+        dmc.write_control(0b1100_0000);
+        dmc.write_address(0x01);
+        dmc.set_enabled(true);
+
+        dmc.load_sample(0);
+        assert!(!dmc.irq());
+        assert!(dmc.is_active());
+        assert_eq!(dmc.current_address, 0xC040);
+    }
+}
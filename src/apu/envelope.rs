@@ -0,0 +1,117 @@
+/// Volume of the pulse and noise channels, either constant or a decaying sawtooth.
+/// Clocked on quarter frames by the frame counter.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Envelope {
+    start: bool,
+    looping: bool,
+    constant: bool,
+    /// Constant volume, or the divider period of the decay
+    volume: u8,
+    divider: u8,
+    decay: u8,
+}
+
+impl Envelope {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// xxLC VVVV
+    ///   || ||||
+    ///   || ++++- Volume, or envelope divider period
+    ///   |+------ Constant volume (1) or envelope (0)
+    ///   +------- Loop the envelope, shared with the length counter halt flag
+    pub fn write(&mut self, data: u8) {
+        self.looping = data & 0b0010_0000 != 0;
+        self.constant = data & 0b0001_0000 != 0;
+        self.volume = data & 0b1111;
+    }
+
+    /// Written by the fourth register of the channel, the decay restarts on the next clock
+    pub fn restart(&mut self) {
+        self.start = true;
+    }
+
+    pub fn clock(&mut self) {
+        if self.start {
+            self.start = false;
+            self.decay = 15;
+            self.divider = self.volume;
+        } else if self.divider == 0 {
+            self.divider = self.volume;
+            if self.decay > 0 {
+                self.decay -= 1;
+            } else if self.looping {
+                self.decay = 15;
+            }
+        } else {
+            self.divider -= 1;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.constant {
+            self.volume
+        } else {
+            self.decay
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_volume() {
+        let mut envelope = Envelope::new();
+        envelope.write(0b0001_0111);
+
+        envelope.restart();
+        envelope.clock();
+        assert_eq!(envelope.output(), 7);
+    }
+
+    #[test]
+    fn decay() {
+        let mut envelope = Envelope::new();
+        envelope.write(1);
+        envelope.restart();
+
+        envelope.clock();
+        assert_eq!(envelope.output(), 15);
+        // The divider reloads with a period of 1 + 1 clocks
+        envelope.clock();
+        assert_eq!(envelope.output(), 15);
+        envelope.clock();
+        assert_eq!(envelope.output(), 14);
+    }
+
+    #[test]
+    fn looping() {
+        let mut envelope = Envelope::new();
+        envelope.write(0b0010_0000);
+        envelope.restart();
+
+        for _ in 0..16 {
+            envelope.clock();
+        }
+        assert_eq!(envelope.output(), 0);
+        envelope.clock();
+        assert_eq!(envelope.output(), 15);
+    }
+
+    #[test]
+    fn stays_silent_without_loop() {
+        let mut envelope = Envelope::new();
+        envelope.write(0);
+        envelope.restart();
+
+        for _ in 0..20 {
+            envelope.clock();
+        }
+        assert_eq!(envelope.output(), 0);
+    }
+}
@@ -0,0 +1,92 @@
+use std::f32::consts::PI;
+
+/// First-order RC filter, run at the output sample rate
+#[derive(Debug, Clone)]
+pub enum Filter {
+    HighPass {
+        alpha: f32,
+        previous_input: f32,
+        previous_output: f32,
+    },
+    LowPass {
+        alpha: f32,
+        previous_output: f32,
+    },
+}
+
+impl Filter {
+    pub fn high_pass(cutoff: f32, sample_rate: u32) -> Self {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate as f32;
+
+        Self::HighPass {
+            alpha: rc / (rc + dt),
+            previous_input: 0.0,
+            previous_output: 0.0,
+        }
+    }
+
+    pub fn low_pass(cutoff: f32, sample_rate: u32) -> Self {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate as f32;
+
+        Self::LowPass {
+            alpha: dt / (rc + dt),
+            previous_output: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        match self {
+            Self::HighPass {
+                alpha,
+                previous_input,
+                previous_output,
+            } => {
+                *previous_output = *alpha * (*previous_output + input - *previous_input);
+                *previous_input = input;
+                *previous_output
+            }
+            Self::LowPass {
+                alpha,
+                previous_output,
+            } => {
+                *previous_output += *alpha * (input - *previous_output);
+                *previous_output
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Peak amplitude of a unit sine at `frequency` once the filter settled
+    fn gain(filter: &mut Filter, frequency: f32) -> f32 {
+        (0..44100)
+            .map(|n| filter.process((2.0 * PI * frequency * n as f32 / 44100.0).sin()))
+            .skip(22050)
+            .fold(0.0, |peak: f32, sample| peak.max(sample.abs()))
+    }
+
+    #[test]
+    fn high_pass() {
+        let mut filter = Filter::high_pass(440.0, 44100);
+        let dc = (0..44100).map(|_| filter.process(1.0)).last().unwrap();
+        assert!(dc.abs() < 1e-3);
+
+        assert!(gain(&mut Filter::high_pass(440.0, 44100), 44.0) < 0.2);
+        assert!(gain(&mut Filter::high_pass(440.0, 44100), 4400.0) > 0.9);
+    }
+
+    #[test]
+    fn low_pass() {
+        let mut filter = Filter::low_pass(14000.0, 44100);
+        let dc = (0..100).map(|_| filter.process(1.0)).last().unwrap();
+        assert!((dc - 1.0).abs() < 1e-3);
+
+        assert!(gain(&mut Filter::low_pass(1000.0, 44100), 100.0) > 0.9);
+        assert!(gain(&mut Filter::low_pass(1000.0, 44100), 10000.0) < 0.2);
+    }
+}
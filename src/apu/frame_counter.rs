@@ -0,0 +1,205 @@
+use crate::Timing;
+
+/// CPU cycles from the start of the sequence to each step, the fifth one is in 5-step mode only
+const NTSC_STEPS: [u32; 5] = [7457, 14913, 22371, 29829, 37281];
+const PAL_STEPS: [u32; 5] = [8313, 16627, 24939, 33253, 41565];
+
+/// Units clocked by a step of the frame counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameClock {
+    /// Envelopes and the triangle's linear counter
+    Quarter,
+    /// Length counters and sweep units, on top of the quarter frame units
+    Half,
+}
+
+/// Frame sequencer driving the envelopes, sweeps and length counters at about 240 Hz.
+///
+/// 4-step mode raises the frame IRQ at the end of every sequence unless inhibited,
+/// 5-step mode never does and clocks the units at about 192 Hz instead.
+#[derive(Debug, Clone)]
+pub struct FrameCounter {
+    steps: &'static [u32; 5],
+    five_step: bool,
+    irq_inhibit: bool,
+    irq: bool,
+    /// CPU cycles since the start of the sequence
+    cycle: u32,
+    /// A $4017 write takes effect after a few cycles: the remaining delay and the new mode
+    pending_write: Option<(u8, bool)>,
+}
+
+impl FrameCounter {
+    pub fn new(timing: Timing) -> Self {
+        Self {
+            steps: match timing {
+                Timing::PAL => &PAL_STEPS,
+                Timing::NTSC | Timing::MultiRegion | Timing::Dendy => &NTSC_STEPS,
+            },
+            five_step: false,
+            irq_inhibit: false,
+            irq: false,
+            cycle: 0,
+            pending_write: None,
+        }
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// MI-- ----
+    /// ||
+    /// |+-------- IRQ inhibit, setting it acknowledges the interrupt
+    /// +--------- Sequencer mode (0: 4-step; 1: 5-step)
+    ///
+    /// The sequence restarts 3 CPU cycles after a write on a put cycle and 4 after one
+    /// on a get cycle, and 5-step mode immediately clocks every unit when it does
+    pub fn write(&mut self, data: u8, put_cycle: bool) {
+        self.irq_inhibit = data & 0b0100_0000 != 0;
+        if self.irq_inhibit {
+            self.irq = false;
+        }
+
+        let delay = if put_cycle { 3 } else { 4 };
+        self.pending_write = Some((delay, data & 0b1000_0000 != 0));
+    }
+
+    pub fn irq(&self) -> bool {
+        self.irq
+    }
+
+    /// $4015 read
+    pub fn acknowledge_irq(&mut self) {
+        self.irq = false;
+    }
+
+    pub fn five_step(&self) -> bool {
+        self.five_step
+    }
+
+    /// Advance by one CPU cycle, returning the units to clock on this cycle
+    pub fn clock(&mut self) -> Option<FrameClock> {
+        if let Some((delay, five_step)) = self.pending_write {
+            if delay > 1 {
+                self.pending_write = Some((delay - 1, five_step));
+            } else {
+                self.pending_write = None;
+                self.five_step = five_step;
+                self.cycle = 0;
+                return five_step.then_some(FrameClock::Half);
+            }
+        }
+
+        self.cycle += 1;
+        let [first, second, third, fourth, fifth] = *self.steps;
+
+        // The flag is raised on the last three cycles of the 4-step sequence
+        if !self.five_step && !self.irq_inhibit && self.cycle + 1 >= fourth {
+            self.irq = true;
+        }
+
+        let end = if self.five_step { fifth } else { fourth };
+        match self.cycle {
+            cycle if cycle == first || cycle == third => Some(FrameClock::Quarter),
+            cycle if cycle == second || cycle == end => Some(FrameClock::Half),
+            cycle if cycle == end + 1 => {
+                self.cycle = 0;
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    /// Cycles at which units are clocked over `cycles` CPU cycles
+    fn run(frame_counter: &mut FrameCounter, cycles: u32) -> Vec<(u32, FrameClock)> {
+        (1..=cycles)
+            .filter_map(|cycle| frame_counter.clock().map(|clock| (cycle, clock)))
+            .collect()
+    }
+
+    #[test]
+    fn four_step() {
+        let mut frame_counter = FrameCounter::new(Timing::NTSC);
+
+        assert_eq!(
+            run(&mut frame_counter, 29830 + 7457),
+            [
+                (7457, FrameClock::Quarter),
+                (14913, FrameClock::Half),
+                (22371, FrameClock::Quarter),
+                (29829, FrameClock::Half),
+                (29830 + 7457, FrameClock::Quarter),
+            ]
+        );
+    }
+
+    #[test]
+    fn five_step() {
+        let mut frame_counter = FrameCounter::new(Timing::NTSC);
+        frame_counter.write(0b1000_0000, true);
+
+        assert_eq!(
+            run(&mut frame_counter, 3 + 37282 + 7457),
+            [
+                (3, FrameClock::Half),
+                (3 + 7457, FrameClock::Quarter),
+                (3 + 14913, FrameClock::Half),
+                (3 + 22371, FrameClock::Quarter),
+                (3 + 37281, FrameClock::Half),
+                (3 + 37282 + 7457, FrameClock::Quarter),
+            ]
+        );
+        assert!(!frame_counter.irq());
+    }
+
+    #[test_case(true, 3 ; "put_cycle")]
+    #[test_case(false, 4 ; "get_cycle")]
+    fn write_delay(put_cycle: bool, delay: u32) {
+        let mut frame_counter = FrameCounter::new(Timing::NTSC);
+        run(&mut frame_counter, 100);
+
+        frame_counter.write(0, put_cycle);
+        assert_eq!(
+            run(&mut frame_counter, delay + 7457).last(),
+            Some(&(delay + 7457, FrameClock::Quarter))
+        );
+    }
+
+    #[test_case(Timing::NTSC, 29828 ; "ntsc")]
+    #[test_case(Timing::PAL, 33252 ; "pal")]
+    fn irq(timing: Timing, cycle: u32) {
+        let mut frame_counter = FrameCounter::new(timing);
+
+        run(&mut frame_counter, cycle - 1);
+        assert!(!frame_counter.irq());
+
+        // The flag is raised again on each of the last three cycles, even when acknowledged
+        for _ in 0..3 {
+            frame_counter.acknowledge_irq();
+            run(&mut frame_counter, 1);
+            assert!(frame_counter.irq());
+        }
+
+        frame_counter.acknowledge_irq();
+        run(&mut frame_counter, 1);
+        assert!(!frame_counter.irq());
+    }
+
+    #[test]
+    fn irq_inhibit() {
+        let mut frame_counter = FrameCounter::new(Timing::NTSC);
+        run(&mut frame_counter, 29830);
+        assert!(frame_counter.irq());
+
+        frame_counter.write(0b0100_0000, true);
+        assert!(!frame_counter.irq());
+        run(&mut frame_counter, 29830 * 2);
+        assert!(!frame_counter.irq());
+    }
+}
@@ -0,0 +1,93 @@
+/// Lengths loaded by the upper 5 bits of the fourth register of each channel, in half frames
+const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14, 12, 16, 24, 18, 48, 20, 96, 22,
+    192, 24, 72, 26, 16, 28, 32, 30,
+];
+
+/// Silences a channel once the note has played for its length, unless halted.
+/// Clocked on half frames by the frame counter.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LengthCounter {
+    enabled: bool,
+    halt: bool,
+    counter: u8,
+}
+
+impl LengthCounter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// $4015 write, disabling the channel clears the counter right away
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.counter = 0;
+        }
+    }
+
+    pub fn set_halt(&mut self, halt: bool) {
+        self.halt = halt;
+    }
+
+    /// Load the length at `index` of the length table, ignored while the channel is disabled
+    pub fn load(&mut self, index: u8) {
+        if self.enabled {
+            self.counter = LENGTH_TABLE[(index & 0b1_1111) as usize];
+        }
+    }
+
+    pub fn clock(&mut self) {
+        if !self.halt && self.counter > 0 {
+            self.counter -= 1;
+        }
+    }
+
+    pub fn counter(&self) -> u8 {
+        self.counter
+    }
+
+    /// Whether the channel is still playing, as reported by $4015
+    pub fn is_active(&self) -> bool {
+        self.counter > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_and_clock() {
+        let mut length_counter = LengthCounter::new();
+        length_counter.set_enabled(true);
+
+        length_counter.load(0b0_0001);
+        assert_eq!(length_counter.counter(), 254);
+        length_counter.clock();
+        assert_eq!(length_counter.counter(), 253);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut length_counter = LengthCounter::new();
+        length_counter.load(0);
+        assert!(!length_counter.is_active());
+
+        length_counter.set_enabled(true);
+        length_counter.load(0);
+        length_counter.set_enabled(false);
+        assert!(!length_counter.is_active());
+    }
+
+    #[test]
+    fn halt() {
+        let mut length_counter = LengthCounter::new();
+        length_counter.set_enabled(true);
+        length_counter.load(3);
+        length_counter.set_halt(true);
+
+        length_counter.clock();
+        assert_eq!(length_counter.counter(), 2);
+    }
+}
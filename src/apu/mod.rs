@@ -0,0 +1,317 @@
+pub mod audio;
+pub mod dmc;
+pub mod envelope;
+pub mod filter;
+pub mod frame_counter;
+pub mod length_counter;
+pub mod noise;
+pub mod pulse;
+pub mod resampler;
+pub mod sweep;
+pub mod triangle;
+
+pub use audio::*;
+pub use dmc::*;
+pub use envelope::*;
+pub use filter::*;
+pub use frame_counter::*;
+pub use length_counter::*;
+pub use noise::*;
+pub use pulse::*;
+pub use resampler::*;
+pub use sweep::*;
+pub use triangle::*;
+
+use crate::Timing;
+
+pub const PULSE_1: u16 = 0x4000;
+pub const PULSE_2: u16 = 0x4004;
+pub const TRIANGLE: u16 = 0x4008;
+pub const NOISE: u16 = 0x400C;
+pub const DMC: u16 = 0x4010;
+pub const APU_REGISTERS_END: u16 = 0x4013;
+pub const APU_STATUS: u16 = 0x4015;
+pub const FRAME_COUNTER: u16 = 0x4017;
+
+bitflags::bitflags! {
+    /// $4015 write: enable channels; read: channels still playing
+    ///
+    /// 7  bit  0
+    /// ---- ----
+    /// IF-D NT21
+    /// || | ||||
+    /// || | |||+- Pulse 1
+    /// || | ||+-- Pulse 2
+    /// || | |+--- Triangle
+    /// || | +---- Noise
+    /// || +------ DMC
+    /// |+-------- Frame interrupt (read only)
+    /// +--------- DMC interrupt (read only)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ChannelStatus: u8 {
+        const PULSE_1   = 0b0000_0001;
+        const PULSE_2   = 0b0000_0010;
+        const TRIANGLE  = 0b0000_0100;
+        const NOISE     = 0b0000_1000;
+        const DMC       = 0b0001_0000;
+        const FRAME_IRQ = 0b0100_0000;
+        const DMC_IRQ   = 0b1000_0000;
+    }
+}
+
+/// Audio processing unit, clocked at the CPU rate by the bus
+#[derive(Debug, Clone)]
+pub struct APU {
+    pub pulse_1: Pulse,
+    pub pulse_2: Pulse,
+    pub triangle: Triangle,
+    pub noise: Noise,
+    pub dmc: Dmc,
+    pub frame_counter: FrameCounter,
+    audio: AudioOutput,
+    /// CPU cycles since power-up, channel timers are clocked on every other one
+    cycles: u64,
+}
+
+impl APU {
+    /// `timing` selects the period tables of the region
+    pub fn new(timing: Timing) -> Self {
+        Self {
+            pulse_1: Pulse::new(PulseChannel::One),
+            pulse_2: Pulse::new(PulseChannel::Two),
+            triangle: Triangle::new(),
+            noise: Noise::new(timing),
+            dmc: Dmc::new(timing),
+            frame_counter: FrameCounter::new(timing),
+            audio: AudioOutput::new(timing, DEFAULT_SAMPLE_RATE),
+            cycles: 0,
+        }
+    }
+
+    /// Write to the channel registers at $4000-$4013, to $4015 or to $4017
+    pub fn write_register(&mut self, addr: u16, data: u8) {
+        match addr {
+            PULSE_1..=0x4003 => Self::write_pulse(&mut self.pulse_1, addr, data),
+            PULSE_2..=0x4007 => Self::write_pulse(&mut self.pulse_2, addr, data),
+            TRIANGLE => self.triangle.write_control(data),
+            0x400A => self.triangle.write_timer_low(data),
+            0x400B => self.triangle.write_timer_high(data),
+            NOISE => self.noise.write_control(data),
+            0x400E => self.noise.write_period(data),
+            0x400F => self.noise.write_length(data),
+            DMC => self.dmc.write_control(data),
+            0x4011 => self.dmc.write_direct_load(data),
+            0x4012 => self.dmc.write_address(data),
+            0x4013 => self.dmc.write_length(data),
+            APU_STATUS => self.write_status(data),
+            FRAME_COUNTER => self.frame_counter.write(data, self.put_cycle()),
+            _ => {}
+        }
+    }
+
+    fn write_pulse(pulse: &mut Pulse, addr: u16, data: u8) {
+        match addr & 0b11 {
+            0 => pulse.write_control(data),
+            1 => pulse.write_sweep(data),
+            2 => pulse.write_timer_low(data),
+            _ => pulse.write_timer_high(data),
+        }
+    }
+
+    fn write_status(&mut self, data: u8) {
+        let status = ChannelStatus::from_bits_truncate(data);
+        self.pulse_1
+            .length_counter
+            .set_enabled(status.contains(ChannelStatus::PULSE_1));
+        self.pulse_2
+            .length_counter
+            .set_enabled(status.contains(ChannelStatus::PULSE_2));
+        self.triangle
+            .length_counter
+            .set_enabled(status.contains(ChannelStatus::TRIANGLE));
+        self.noise
+            .length_counter
+            .set_enabled(status.contains(ChannelStatus::NOISE));
+        self.dmc.set_enabled(status.contains(ChannelStatus::DMC));
+    }
+
+    /// $4015 read, acknowledging the frame interrupt
+    pub fn read_status(&mut self) -> u8 {
+        let status = self.peek_status();
+        self.frame_counter.acknowledge_irq();
+        status
+    }
+
+    /// The value `read_status` would return, without any side effect
+    pub fn peek_status(&self) -> u8 {
+        let mut status = ChannelStatus::empty();
+        status.set(
+            ChannelStatus::PULSE_1,
+            self.pulse_1.length_counter.is_active(),
+        );
+        status.set(
+            ChannelStatus::PULSE_2,
+            self.pulse_2.length_counter.is_active(),
+        );
+        status.set(
+            ChannelStatus::TRIANGLE,
+            self.triangle.length_counter.is_active(),
+        );
+        status.set(ChannelStatus::NOISE, self.noise.length_counter.is_active());
+        status.set(ChannelStatus::DMC, self.dmc.is_active());
+        status.set(ChannelStatus::FRAME_IRQ, self.frame_counter.irq());
+        status.set(ChannelStatus::DMC_IRQ, self.dmc.irq());
+        status.bits()
+    }
+
+    /// Advance by one CPU cycle
+    pub fn tick(&mut self) {
+        self.cycles += 1;
+        self.triangle.clock_timer();
+        self.noise.clock_timer();
+        self.dmc.clock_timer();
+        if self.cycles.is_multiple_of(2) {
+            self.pulse_1.clock_timer();
+            self.pulse_2.clock_timer();
+        }
+
+        match self.frame_counter.clock() {
+            Some(FrameClock::Quarter) => self.clock_quarter_frame(),
+            Some(FrameClock::Half) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            }
+            None => {}
+        }
+
+        self.audio.clock(self.output());
+    }
+
+    /// Mixer output, 0.0 to ~1.0
+    pub fn output(&self) -> f32 {
+        mix(
+            self.pulse_1.output(),
+            self.pulse_2.output(),
+            self.triangle.output(),
+            self.noise.output(),
+            self.dmc.output(),
+        )
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.audio.sample_rate()
+    }
+
+    /// Resample the output to `sample_rate` Hz from now on, 44.1 kHz by default
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.audio.set_sample_rate(sample_rate);
+    }
+
+    /// Audio produced since the last call, typically pulled once per frame
+    pub fn samples(&mut self) -> Vec<f32> {
+        self.audio.samples()
+    }
+
+    /// Audio produced since the last call as signed 16-bit PCM
+    pub fn samples_i16(&mut self) -> Vec<i16> {
+        self.audio.samples_i16()
+    }
+
+    /// Whether the last cycle was a put cycle, the DMA only reads on the get cycles between them
+    pub fn put_cycle(&self) -> bool {
+        self.cycles.is_multiple_of(2)
+    }
+
+    /// Clock the envelopes and the linear counter
+    pub fn clock_quarter_frame(&mut self) {
+        self.pulse_1.clock_quarter_frame();
+        self.pulse_2.clock_quarter_frame();
+        self.triangle.clock_quarter_frame();
+        self.noise.clock_quarter_frame();
+    }
+
+    /// Clock the length counters and sweep units
+    pub fn clock_half_frame(&mut self) {
+        self.pulse_1.clock_half_frame();
+        self.pulse_2.clock_half_frame();
+        self.triangle.clock_half_frame();
+        self.noise.clock_half_frame();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status() {
+        let mut apu = APU::new(Timing::NTSC);
+
+        // Loading a length is ignored while the channel is disabled
+        apu.write_register(0x4003, 0b0000_1000);
+        assert_eq!(apu.read_status(), 0);
+
+        apu.write_register(APU_STATUS, 0b0000_1111);
+        apu.write_register(0x4003, 0b0000_1000);
+        apu.write_register(0x4007, 0b0000_1000);
+        apu.write_register(0x400B, 0b0000_1000);
+        apu.write_register(0x400F, 0b0000_1000);
+        assert_eq!(apu.read_status(), 0b0000_1111);
+
+        apu.write_register(APU_STATUS, 0b0000_1010);
+        assert_eq!(apu.read_status(), 0b0000_1010);
+    }
+
+    #[test]
+    fn dmc_status() {
+        let mut apu = APU::new(Timing::NTSC);
+        apu.write_register(DMC, 0b1000_0000);
+        apu.write_register(APU_STATUS, 0b0001_0000);
+        assert_eq!(apu.read_status(), 0b0001_0000);
+
+        apu.dmc.load_sample(0);
+        assert_eq!(apu.read_status(), 0b1000_0000);
+
+        // Writing $4015 acknowledges the interrupt
+        apu.write_register(APU_STATUS, 0);
+        assert_eq!(apu.read_status(), 0);
+    }
+
+    #[test]
+    fn samples() {
+        let mut apu = APU::new(Timing::NTSC);
+        apu.write_register(APU_STATUS, 0b0000_0001);
+        // 50% duty, constant volume 15, about 440 Hz
+        apu.write_register(0x4000, 0b1001_1111);
+        apu.write_register(0x4002, 0xFD);
+        apu.write_register(0x4003, 0b0000_1000);
+
+        for _ in 0..29781 {
+            apu.tick();
+        }
+        let samples = apu.samples();
+        assert_eq!(samples.len(), 733);
+        assert!(samples.iter().any(|&sample| sample > 0.05));
+        assert!(samples.iter().any(|&sample| sample < -0.05));
+    }
+
+    #[test]
+    fn pulse_timers_run_at_half_the_cpu_rate() {
+        let mut apu = APU::new(Timing::NTSC);
+        apu.write_register(APU_STATUS, 0b0000_0001);
+        // 12.5% duty, constant volume 15, shortest audible period
+        apu.write_register(0x4000, 0b0001_1111);
+        apu.write_register(0x4002, 8);
+        apu.write_register(0x4003, 0b0000_1000);
+
+        // The high step of the duty cycle is the 7th one, 9 APU cycles each
+        let levels = (0..8 * 9 * 2)
+            .map(|_| {
+                apu.tick();
+                apu.pulse_1.output()
+            })
+            .collect::<Vec<_>>();
+        let high = levels.iter().filter(|&&level| level == 15).count();
+        assert_eq!(high, 9 * 2);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::Timing;
+
+use super::{Envelope, LengthCounter};
+
+/// Timer periods selected by $400E, in CPU cycles
+const NTSC_PERIODS: [u16; 16] = [
+    4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068,
+];
+const PAL_PERIODS: [u16; 16] = [
+    4, 8, 14, 30, 60, 88, 118, 148, 188, 236, 354, 472, 708, 944, 1890, 3778,
+];
+
+/// Pseudo-random noise channel, driven by a 15-bit linear feedback shift register
+#[derive(Debug, Clone)]
+pub struct Noise {
+    periods: &'static [u16; 16],
+    /// Short mode feeds back bit 6 instead of bit 1, giving a 93-step metallic tone
+    short_mode: bool,
+    timer_period: u16,
+    timer: u16,
+    shift_register: u16,
+    pub envelope: Envelope,
+    pub length_counter: LengthCounter,
+}
+
+impl Noise {
+    pub fn new(timing: Timing) -> Self {
+        Self {
+            periods: match timing {
+                Timing::PAL => &PAL_PERIODS,
+                Timing::NTSC | Timing::MultiRegion | Timing::Dendy => &NTSC_PERIODS,
+            },
+            short_mode: false,
+            timer_period: NTSC_PERIODS[0],
+            timer: 0,
+            shift_register: 1,
+            envelope: Envelope::new(),
+            length_counter: LengthCounter::new(),
+        }
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// xxLC VVVV
+    ///   || ||||
+    ///   || ++++- Volume, or envelope divider period
+    ///   |+------ Constant volume
+    ///   +------- Length counter halt, envelope loop
+    pub fn write_control(&mut self, data: u8) {
+        self.length_counter.set_halt(data & 0b0010_0000 != 0);
+        self.envelope.write(data);
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// Mxxx PPPP
+    /// |    ||||
+    /// |    ++++- Index in the period table
+    /// +--------- Short mode
+    pub fn write_period(&mut self, data: u8) {
+        self.short_mode = data & 0b1000_0000 != 0;
+        self.timer_period = self.periods[(data & 0b1111) as usize];
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// LLLL Lxxx
+    /// |||| |
+    /// ++++-+---- Length counter load
+    ///
+    /// Restarts the envelope
+    pub fn write_length(&mut self, data: u8) {
+        self.length_counter.load(data >> 3);
+        self.envelope.restart();
+    }
+
+    /// Period in CPU cycles
+    pub fn timer_period(&self) -> u16 {
+        self.timer_period
+    }
+
+    pub fn shift_register(&self) -> u16 {
+        self.shift_register
+    }
+
+    /// Clocked every CPU cycle, the periods being counted in CPU cycles
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+
+        self.timer = self.timer_period - 1;
+        let tap = if self.short_mode { 6 } else { 1 };
+        let feedback = (self.shift_register ^ (self.shift_register >> tap)) & 1;
+        self.shift_register = (self.shift_register >> 1) | (feedback << 14);
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+    }
+
+    /// Current level, 0 to 15
+    pub fn output(&self) -> u8 {
+        if self.shift_register & 1 != 0 || !self.length_counter.is_active() {
+            0
+        } else {
+            self.envelope.output()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn sequence_length(short_mode: bool) -> usize {
+        let mut noise = Noise::new(Timing::NTSC);
+        noise.write_period(if short_mode { 0b1000_0000 } else { 0 });
+        noise.clock_timer();
+        let start = noise.shift_register();
+
+        let mut steps = 1;
+        loop {
+            for _ in 0..noise.timer_period() {
+                noise.clock_timer();
+            }
+            if noise.shift_register() == start {
+                return steps;
+            }
+            steps += 1;
+        }
+    }
+
+    #[test]
+    fn long_mode() {
+        assert_eq!(sequence_length(false), 32767);
+    }
+
+    #[test]
+    fn short_mode() {
+        assert_eq!(sequence_length(true), 93);
+    }
+
+    #[test_case(Timing::NTSC, 4068 ; "ntsc")]
+    #[test_case(Timing::PAL, 3778 ; "pal")]
+    fn period(timing: Timing, period: u16) {
+        let mut noise = Noise::new(timing);
+        noise.write_period(0x0F);
+
+        assert_eq!(noise.timer_period(), period);
+    }
+
+    #[test]
+    fn output() {
+        let mut noise = Noise::new(Timing::NTSC);
+        noise.length_counter.set_enabled(true);
+        noise.write_control(0b0001_0101);
+        noise.write_length(0b0000_1000);
+
+        // Bit 0 of the shift register mutes the channel
+        assert_eq!(noise.output(), 0);
+        noise.clock_timer();
+        assert_eq!(noise.shift_register() & 1, 0);
+        assert_eq!(noise.output(), 5);
+    }
+}
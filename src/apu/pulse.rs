@@ -0,0 +1,188 @@
+use super::{Envelope, LengthCounter, Sweep};
+
+/// Waveforms selected by the duty bits, indexed by the sequencer counting down from 0
+const DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 1, 1, 0, 0, 0, 0, 0],
+    [0, 1, 1, 1, 1, 0, 0, 0],
+    [1, 0, 0, 1, 1, 1, 1, 1],
+];
+
+/// The two pulse channels only differ by how their sweep units negate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseChannel {
+    /// $4000-$4003
+    One,
+    /// $4004-$4007
+    Two,
+}
+
+/// Square wave channel
+#[derive(Debug, Clone)]
+pub struct Pulse {
+    duty: u8,
+    sequencer: u8,
+    timer_period: u16,
+    timer: u16,
+    pub envelope: Envelope,
+    pub sweep: Sweep,
+    pub length_counter: LengthCounter,
+}
+
+impl Pulse {
+    pub fn new(channel: PulseChannel) -> Self {
+        Self {
+            duty: 0,
+            sequencer: 0,
+            timer_period: 0,
+            timer: 0,
+            envelope: Envelope::new(),
+            sweep: Sweep::new(channel == PulseChannel::One),
+            length_counter: LengthCounter::new(),
+        }
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// DDLC VVVV
+    /// |||| ||||
+    /// |||| ++++- Volume, or envelope divider period
+    /// |||+------ Constant volume
+    /// ||+------- Length counter halt, envelope loop
+    /// ++-------- Duty cycle (12.5%, 25%, 50%, 25% negated)
+    pub fn write_control(&mut self, data: u8) {
+        self.duty = data >> 6;
+        self.length_counter.set_halt(data & 0b0010_0000 != 0);
+        self.envelope.write(data);
+    }
+
+    pub fn write_sweep(&mut self, data: u8) {
+        self.sweep.write(data);
+    }
+
+    /// Low 8 bits of the timer period
+    pub fn write_timer_low(&mut self, data: u8) {
+        self.timer_period = (self.timer_period & 0x0700) | data as u16;
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// LLLL LTTT
+    /// |||| ||||
+    /// |||| |+++- High 3 bits of the timer period
+    /// ++++-+---- Length counter load
+    ///
+    /// Restarts the sequencer and the envelope
+    pub fn write_timer_high(&mut self, data: u8) {
+        self.timer_period = (self.timer_period & 0x00FF) | ((data as u16 & 0b111) << 8);
+        self.length_counter.load(data >> 3);
+        self.sequencer = 0;
+        self.envelope.restart();
+    }
+
+    pub fn timer_period(&self) -> u16 {
+        self.timer_period
+    }
+
+    /// Clocked every APU cycle (every other CPU cycle)
+    pub fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            self.sequencer = self.sequencer.wrapping_sub(1) & 0b111;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+        self.sweep.clock(&mut self.timer_period);
+    }
+
+    /// Current level, 0 to 15
+    pub fn output(&self) -> u8 {
+        if DUTY_TABLE[self.duty as usize][self.sequencer as usize] == 0
+            || !self.length_counter.is_active()
+            || self.sweep.mutes(self.timer_period)
+        {
+            0
+        } else {
+            self.envelope.output()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playing_pulse() -> Pulse {
+        let mut pulse = Pulse::new(PulseChannel::One);
+        pulse.length_counter.set_enabled(true);
+        // 50% duty, constant volume 9
+        pulse.write_control(0b1001_1001);
+        pulse.write_timer_low(0x10);
+        pulse.write_timer_high(0b0000_1000);
+        pulse
+    }
+
+    fn waveform(pulse: &mut Pulse) -> Vec<u8> {
+        (0..8)
+            .map(|_| {
+                for _ in 0..=pulse.timer_period() {
+                    pulse.clock_timer();
+                }
+                pulse.output()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn duty_cycle() {
+        let mut pulse = playing_pulse();
+
+        assert_eq!(pulse.output(), 0);
+        assert_eq!(waveform(&mut pulse), [0, 0, 0, 9, 9, 9, 9, 0]);
+    }
+
+    #[test]
+    fn write_timer() {
+        let mut pulse = playing_pulse();
+        pulse.write_timer_low(0x34);
+        pulse.write_timer_high(0b1111_1010);
+
+        assert_eq!(pulse.timer_period(), 0x234);
+        assert_eq!(pulse.length_counter.counter(), 30);
+    }
+
+    #[test]
+    fn silenced_by_length_counter() {
+        let mut pulse = playing_pulse();
+        pulse.length_counter.set_enabled(false);
+
+        assert!(waveform(&mut pulse).iter().all(|&level| level == 0));
+    }
+
+    #[test]
+    fn silenced_by_sweep() {
+        let mut pulse = playing_pulse();
+        pulse.write_timer_low(0x07);
+        pulse.write_timer_high(0b0000_1000);
+
+        assert!(waveform(&mut pulse).iter().all(|&level| level == 0));
+    }
+
+    #[test]
+    fn half_frame_sweeps_period() {
+        let mut pulse = playing_pulse();
+        pulse.write_sweep(0b1000_0001);
+
+        pulse.clock_half_frame();
+        assert_eq!(pulse.timer_period(), 0x18);
+        assert_eq!(pulse.length_counter.counter(), 253);
+    }
+}
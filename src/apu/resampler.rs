@@ -0,0 +1,151 @@
+use std::f64::consts::PI;
+
+/// Fractional positions between two output samples the kernel is computed for
+const PHASES: usize = 32;
+/// Output samples a single step is spread over
+const TAPS: usize = 16;
+/// Cutoff of the kernel, relative to the Nyquist frequency of the output
+const CUTOFF: f64 = 0.9;
+
+/// Band-limited resampler, turning a signal clocked at the CPU rate into host audio samples.
+///
+/// Rather than sampling the level, every change is recorded as a step whose impulse, a windowed
+/// sinc, is added to the samples around it. The output is the running sum of those impulses,
+/// so squares and triangles come out without the aliasing of naive decimation.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    /// Output samples per input clock
+    ratio: f64,
+    kernel: Vec<[f32; TAPS]>,
+    /// Impulses of the pending samples, the first one being the next sample to output
+    buffer: Vec<f32>,
+    /// Position of the first clock since the last read, in output samples
+    offset: f64,
+    clocks: u64,
+    level: f32,
+    sum: f32,
+}
+
+impl Resampler {
+    pub fn new(clock_rate: f64, sample_rate: u32) -> Self {
+        Self {
+            ratio: sample_rate as f64 / clock_rate,
+            kernel: (0..=PHASES).map(Self::kernel).collect(),
+            buffer: vec![0.0; TAPS],
+            offset: 0.0,
+            clocks: 0,
+            level: 0.0,
+            sum: 0.0,
+        }
+    }
+
+    /// Blackman windowed sinc centered between taps `TAPS / 2 - 1` and `TAPS / 2`,
+    /// shifted by `phase / PHASES` of a sample and normalized so every step settles exactly
+    fn kernel(phase: usize) -> [f32; TAPS] {
+        let shift = phase as f64 / PHASES as f64;
+        let mut taps = [0.0; TAPS];
+
+        for (i, tap) in taps.iter_mut().enumerate() {
+            let t = i as f64 - (TAPS / 2) as f64 + 1.0 - shift;
+            let x = PI * CUTOFF * t;
+            let sinc = if x == 0.0 { 1.0 } else { x.sin() / x };
+            let w = 2.0 * PI * t / TAPS as f64;
+            let window = 0.42 + 0.5 * w.cos() + 0.08 * (2.0 * w).cos();
+            *tap = (sinc * window) as f32;
+        }
+
+        let total = taps.iter().sum::<f32>();
+        taps.map(|tap| tap / total)
+    }
+
+    /// Level of the input signal on the current clock, before advancing to the next one
+    pub fn clock(&mut self, level: f32) {
+        if level != self.level {
+            let position = self.offset + self.clocks as f64 * self.ratio;
+            let index = position as usize;
+            let phase = ((position - index as f64) * PHASES as f64).round() as usize;
+            let delta = level - self.level;
+            self.level = level;
+
+            if self.buffer.len() < index + TAPS {
+                self.buffer.resize(index + TAPS, 0.0);
+            }
+            for (sample, tap) in self.buffer[index..].iter_mut().zip(self.kernel[phase]) {
+                *sample += delta * tap;
+            }
+        }
+
+        self.clocks += 1;
+    }
+
+    /// Append every sample completed since the last read to `samples`
+    pub fn read(&mut self, samples: &mut Vec<f32>) {
+        let end = self.offset + self.clocks as f64 * self.ratio;
+        let count = end as usize;
+
+        if self.buffer.len() < count + TAPS {
+            self.buffer.resize(count + TAPS, 0.0);
+        }
+        for impulse in self.buffer.drain(..count) {
+            self.sum += impulse;
+            samples.push(self.sum);
+        }
+
+        self.offset = end - count as f64;
+        self.clocks = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_count() {
+        let mut resampler = Resampler::new(1_789_773.0, 44100);
+        let mut samples = Vec::new();
+
+        for _ in 0..60 {
+            for _ in 0..29830 {
+                resampler.clock(0.0);
+            }
+            resampler.read(&mut samples);
+        }
+
+        // 60 4-step frame counter sequences are just short of a second
+        assert_eq!(
+            samples.len(),
+            (60.0 * 29830.0 * 44100.0 / 1_789_773.0) as usize
+        );
+    }
+
+    #[test]
+    fn step_settles() {
+        let mut resampler = Resampler::new(1_789_773.0, 44100);
+        let mut samples = Vec::new();
+
+        for _ in 0..10_000 {
+            resampler.clock(0.5);
+        }
+        resampler.read(&mut samples);
+
+        assert!(samples[0].abs() < 1e-2);
+        assert!((samples.last().unwrap() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn band_limited() {
+        let mut resampler = Resampler::new(1_789_773.0, 44100);
+        let mut samples = Vec::new();
+
+        // A square wave far above the output Nyquist frequency averages out
+        for cycle in 0..100_000 {
+            resampler.clock(if cycle % 8 < 4 { 1.0 } else { 0.0 });
+        }
+        resampler.read(&mut samples);
+
+        for sample in &samples[TAPS..] {
+            assert!((sample - 0.5).abs() < 0.1, "{sample}");
+        }
+    }
+}
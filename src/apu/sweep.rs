@@ -0,0 +1,121 @@
+/// Largest timer period the sweep unit lets a pulse channel play
+const MAX_PERIOD: u16 = 0x7FF;
+
+/// Periodically adjusts the timer period of a pulse channel to bend its pitch.
+/// Clocked on half frames by the frame counter.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Sweep {
+    enabled: bool,
+    period: u8,
+    negate: bool,
+    shift: u8,
+    divider: u8,
+    reload: bool,
+    /// Pulse 1 negates with the ones' complement, subtracting one more than pulse 2
+    ones_complement: bool,
+}
+
+impl Sweep {
+    pub fn new(ones_complement: bool) -> Self {
+        Self {
+            ones_complement,
+            ..Default::default()
+        }
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// EPPP NSSS
+    /// |||| ||||
+    /// |||| |+++- Shift count
+    /// |||| +---- Negate
+    /// |+++------ Divider period, minus one
+    /// +--------- Enabled
+    pub fn write(&mut self, data: u8) {
+        self.enabled = data & 0b1000_0000 != 0;
+        self.period = (data >> 4) & 0b111;
+        self.negate = data & 0b1000 != 0;
+        self.shift = data & 0b111;
+        self.reload = true;
+    }
+
+    /// Period the channel would be changed to
+    pub fn target_period(&self, period: u16) -> u16 {
+        let change = period >> self.shift;
+        match (self.negate, self.ones_complement) {
+            (false, _) => period + change,
+            (true, false) => period.saturating_sub(change),
+            (true, true) => period.saturating_sub(change + 1),
+        }
+    }
+
+    /// The channel is silenced when its period is too short or the target overflows,
+    /// even while the sweep is disabled
+    pub fn mutes(&self, period: u16) -> bool {
+        period < 8 || self.target_period(period) > MAX_PERIOD
+    }
+
+    pub fn clock(&mut self, period: &mut u16) {
+        if self.divider == 0 && self.enabled && self.shift != 0 && !self.mutes(*period) {
+            *period = self.target_period(*period);
+        }
+
+        if self.divider == 0 || self.reload {
+            self.divider = self.period;
+            self.reload = false;
+        } else {
+            self.divider -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(false, 0b0000_0001, 0x180 ; "add")]
+    #[test_case(false, 0b0000_1001, 0x080 ; "negate_twos_complement")]
+    #[test_case(true, 0b0000_1001, 0x07F ; "negate_ones_complement")]
+    fn target_period(ones_complement: bool, data: u8, target: u16) {
+        let mut sweep = Sweep::new(ones_complement);
+        sweep.write(data);
+
+        assert_eq!(sweep.target_period(0x100), target);
+    }
+
+    #[test_case(0x007, 0 ; "period_too_short")]
+    #[test_case(0x600, 1 ; "target_overflows")]
+    fn mutes(period: u16, shift: u8) {
+        let mut sweep = Sweep::new(false);
+        sweep.write(shift);
+
+        assert!(sweep.mutes(period));
+    }
+
+    #[test]
+    fn clock() {
+        let mut sweep = Sweep::new(false);
+        sweep.write(0b1001_0001);
+        let mut period = 0x100;
+
+        // The period changes whenever the divider reaches 0, every 1 + 1 clocks
+        sweep.clock(&mut period);
+        assert_eq!(period, 0x180);
+        sweep.clock(&mut period);
+        assert_eq!(period, 0x180);
+        sweep.clock(&mut period);
+        assert_eq!(period, 0x240);
+    }
+
+    #[test]
+    fn muted_period_is_not_updated() {
+        let mut sweep = Sweep::new(false);
+        sweep.write(0b1000_0001);
+        let mut period = 0x600;
+
+        sweep.clock(&mut period);
+        assert_eq!(period, 0x600);
+    }
+}
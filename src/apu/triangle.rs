@@ -0,0 +1,204 @@
+use super::LengthCounter;
+
+/// Steps of the triangle wave
+const SEQUENCE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12,
+    13, 14, 15,
+];
+
+/// Second duration counter of the triangle channel, with a finer resolution than the length
+/// counter. Clocked on quarter frames by the frame counter.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LinearCounter {
+    control: bool,
+    reload_value: u8,
+    reload: bool,
+    counter: u8,
+}
+
+impl LinearCounter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn clock(&mut self) {
+        if self.reload {
+            self.counter = self.reload_value;
+        } else if self.counter > 0 {
+            self.counter -= 1;
+        }
+
+        if !self.control {
+            self.reload = false;
+        }
+    }
+
+    pub fn counter(&self) -> u8 {
+        self.counter
+    }
+}
+
+/// Triangle wave channel, without volume control
+#[derive(Debug, Clone, Default)]
+pub struct Triangle {
+    sequencer: u8,
+    timer_period: u16,
+    timer: u16,
+    pub linear_counter: LinearCounter,
+    pub length_counter: LengthCounter,
+}
+
+impl Triangle {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// CRRR RRRR
+    /// |||| ||||
+    /// |+++-++++- Linear counter reload value
+    /// +--------- Length counter halt, linear counter control
+    pub fn write_control(&mut self, data: u8) {
+        let control = data & 0b1000_0000 != 0;
+        self.linear_counter.control = control;
+        self.linear_counter.reload_value = data & 0b0111_1111;
+        self.length_counter.set_halt(control);
+    }
+
+    /// Low 8 bits of the timer period
+    pub fn write_timer_low(&mut self, data: u8) {
+        self.timer_period = (self.timer_period & 0x0700) | data as u16;
+    }
+
+    /// 7  bit  0
+    /// ---- ----
+    /// LLLL LTTT
+    /// |||| ||||
+    /// |||| |+++- High 3 bits of the timer period
+    /// ++++-+---- Length counter load
+    ///
+    /// Sets the linear counter reload flag
+    pub fn write_timer_high(&mut self, data: u8) {
+        self.timer_period = (self.timer_period & 0x00FF) | ((data as u16 & 0b111) << 8);
+        self.length_counter.load(data >> 3);
+        self.linear_counter.reload = true;
+    }
+
+    pub fn timer_period(&self) -> u16 {
+        self.timer_period
+    }
+
+    /// Clocked every CPU cycle
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+
+        self.timer = self.timer_period;
+        // Periods below 2 produce an ultrasonic frequency heard as a pop,
+        // the sequencer is held instead like most emulators do
+        if self.linear_counter.counter() > 0
+            && self.length_counter.is_active()
+            && self.timer_period >= 2
+        {
+            self.sequencer = (self.sequencer + 1) % SEQUENCE.len() as u8;
+        }
+    }
+
+    pub fn clock_quarter_frame(&mut self) {
+        self.linear_counter.clock();
+    }
+
+    pub fn clock_half_frame(&mut self) {
+        self.length_counter.clock();
+    }
+
+    /// Current level, 0 to 15. Silencing the channel stops the sequencer on its current step
+    /// rather than dropping the output to 0
+    pub fn output(&self) -> u8 {
+        SEQUENCE[self.sequencer as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playing_triangle(period: u16) -> Triangle {
+        let mut triangle = Triangle::new();
+        triangle.length_counter.set_enabled(true);
+        triangle.write_control(0b0000_0100);
+        triangle.write_timer_low(period as u8);
+        triangle.write_timer_high(0b0000_1000 | (period >> 8) as u8);
+        triangle.clock_quarter_frame();
+        triangle
+    }
+
+    fn steps(triangle: &mut Triangle, count: usize) -> Vec<u8> {
+        (0..count)
+            .map(|_| {
+                for _ in 0..=triangle.timer_period() {
+                    triangle.clock_timer();
+                }
+                triangle.output()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sequence() {
+        let mut triangle = playing_triangle(0x10);
+
+        let levels = steps(&mut triangle, 32);
+        assert_eq!(levels[..3], [14, 13, 12]);
+        assert_eq!(levels[14..18], [0, 0, 1, 2]);
+        assert_eq!(levels[31], 15);
+    }
+
+    #[test]
+    fn linear_counter() {
+        let mut triangle = playing_triangle(0x10);
+        assert_eq!(triangle.linear_counter.counter(), 4);
+
+        // The reload flag is cleared by the first clock without the control flag
+        for _ in 0..4 {
+            triangle.clock_quarter_frame();
+        }
+        assert_eq!(triangle.linear_counter.counter(), 0);
+
+        let levels = steps(&mut triangle, 4);
+        assert_eq!(levels, [15; 4]);
+    }
+
+    #[test]
+    fn linear_counter_control_keeps_reloading() {
+        let mut triangle = playing_triangle(0x10);
+        triangle.write_control(0b1000_0100);
+        triangle.write_timer_high(0b0000_1000);
+
+        for _ in 0..8 {
+            triangle.clock_quarter_frame();
+        }
+        assert_eq!(triangle.linear_counter.counter(), 4);
+    }
+
+    #[test]
+    fn ultrasonic_period_holds_the_sequencer() {
+        let mut triangle = playing_triangle(1);
+
+        let levels = steps(&mut triangle, 4);
+        assert_eq!(levels, [15; 4]);
+    }
+
+    #[test]
+    fn silenced_by_length_counter() {
+        let mut triangle = playing_triangle(0x10);
+        steps(&mut triangle, 3);
+        triangle.length_counter.set_enabled(false);
+
+        let levels = steps(&mut triangle, 4);
+        assert_eq!(levels, [12; 4]);
+    }
+}
@@ -1,40 +1,144 @@
+use std::{io, path::Path};
+
 use crate::{
+    apu::*,
+    joypad::*,
+    mapper::*,
     ppu::{registers::*, *},
-    Mem, Rom,
+    IrqSource, Mem, Rom, SaveFile,
 };
 
 #[derive(Debug)]
 pub struct Bus {
     pub cpu_vram: [u8; 2048],
-    pub prg_rom: Vec<u8>,
+    pub mapper: SharedMapper,
     pub ppu: PPU,
+    pub apu: APU,
     pub cycles: usize,
+    irq_lines: IrqSource,
+    controllers: [Box<dyn InputDevice>; 2],
+    /// Last value driven on the CPU data bus, seen in the bits a read leaves floating
+    open_bus: u8,
+    save_file: Option<SaveFile>,
+    /// Page written to $4014, copied to OAM once the CPU is halted on its next read
+    oam_dma: Option<u8>,
 }
 
 impl Bus {
-    pub fn new(rom: Rom) -> Self {
-        Self {
+    pub fn new(rom: Rom) -> Result<Self, MapperError> {
+        let timing = rom.timing;
+        let mapper = new_mapper(rom)?;
+
+        Ok(Self {
             cpu_vram: [0; 2048],
-            prg_rom: rom.prg_rom,
-            ppu: PPU::new(rom.chr_rom, rom.screen_mirroring),
+            ppu: PPU::new(mapper.clone()),
+            apu: APU::new(timing),
+            mapper,
             cycles: 7,
-        }
+            irq_lines: IrqSource::empty(),
+            controllers: [Box::new(Joypad::new()), Box::new(Joypad::new())],
+            open_bus: 0,
+            save_file: None,
+            oam_dma: None,
+        })
     }
 
-    pub fn insert_rom(&mut self, rom: Rom) {
-        self.prg_rom = rom.prg_rom;
-        self.ppu = PPU::new(rom.chr_rom, rom.screen_mirroring);
+    pub fn insert_rom(&mut self, rom: Rom) -> Result<(), MapperError> {
+        let timing = rom.timing;
+        let mapper = new_mapper(rom)?;
+        // Dropping the save file flushes the battery RAM of the previous cartridge
+        self.save_file = None;
+        self.mapper = mapper;
+        self.ppu = PPU::new(self.mapper.clone());
+        self.apu = APU::new(timing);
+        self.oam_dma = None;
         self.cycles = 7;
+        Ok(())
+    }
+
+    /// Keep the battery RAM of the cartridge in the `.sav` file next to `rom_path`,
+    /// loading it if it exists. Does nothing for cartridges without a battery
+    pub fn load_save_file(&mut self, rom_path: impl AsRef<Path>) -> io::Result<()> {
+        self.save_file = SaveFile::open(rom_path, self.mapper.clone())?;
+        Ok(())
+    }
+
+    /// Write the battery RAM to its save file, it is also written when the bus is dropped
+    pub fn flush_save_file(&self) -> io::Result<()> {
+        match &self.save_file {
+            Some(save_file) => save_file.flush(),
+            None => Ok(()),
+        }
     }
 
-    pub fn tick(&mut self, cycles: u8) {
+    /// Advance the bus by `cycles` CPU cycles, returns whether the PPU completed a frame
+    pub fn tick(&mut self, cycles: u8) -> bool {
         self.cycles += cycles as usize;
-        self.ppu.tick(cycles * 3);
+        for _ in 0..cycles {
+            self.mapper.borrow_mut().cpu_clock();
+            self.apu.tick();
+        }
+        self.ppu.tick(cycles * 3)
+    }
+
+    /// DMA read of the next DMC sample byte, driving the data bus like any CPU read
+    pub fn dmc_fetch(&mut self, addr: u16) {
+        let data = self.mem_read(addr);
+        self.apu.dmc.load_sample(data);
+    }
+
+    /// The page of a pending OAM DMA, clearing the request
+    pub fn take_oam_dma(&mut self) -> Option<u8> {
+        self.oam_dma.take()
     }
 
     pub fn poll_nmi_interrupt(&mut self) -> Option<()> {
         self.ppu.poll_nmi_interrupt()
     }
+
+    pub fn nmi_pending(&self) -> bool {
+        self.ppu.nmi_pending()
+    }
+
+    /// Plug `device` into `port`, replacing whatever was connected
+    pub fn connect(&mut self, port: ControllerPort, device: Box<dyn InputDevice>) {
+        self.controllers[port as usize] = device;
+    }
+
+    /// Update the buttons held on the device plugged into `port`
+    pub fn set_buttons(&mut self, port: ControllerPort, buttons: JoypadButton) {
+        self.controllers[port as usize].set_buttons(buttons);
+    }
+
+    /// Controllers only drive D0-D4, the rest of the byte is open bus
+    fn read_controller(&mut self, port: ControllerPort) -> u8 {
+        let data = self.controllers[port as usize].read();
+        (self.open_bus & 0b1110_0000) | (data & 0b0001_1111)
+    }
+
+    fn peek_controller(&self, port: ControllerPort) -> u8 {
+        let data = self.controllers[port as usize].peek();
+        (self.open_bus & 0b1110_0000) | (data & 0b0001_1111)
+    }
+
+    /// Assert or release the IRQ line on behalf of `source`
+    pub fn set_irq(&mut self, source: IrqSource, asserted: bool) {
+        self.irq_lines.set(source, asserted);
+    }
+
+    /// Every device currently asserting the IRQ line
+    pub fn irq_sources(&self) -> IrqSource {
+        let mut sources = self.irq_lines;
+        sources.set(IrqSource::MAPPER, self.mapper.borrow().irq());
+        sources.set(IrqSource::FRAME_COUNTER, self.apu.frame_counter.irq());
+        sources.set(IrqSource::DMC, self.apu.dmc.irq());
+        sources
+    }
+
+    /// The IRQ line is level triggered, it stays asserted until every source is acknowledged
+    pub fn irq(&self) -> bool {
+        !self.irq_sources().is_empty()
+    }
 }
 
 pub const RAM: u16 = 0;
@@ -43,6 +147,12 @@ pub const RAM_MIRRORS_END: u16 = 0x1FFF;
 pub const PPU_REGISTERS: u16 = 0x2008;
 pub const PPU_REGISTERS_MIRRORS_END: u16 = 0x3FFF;
 
+pub const CARTRIDGE: u16 = 0x4020;
+pub const CARTRIDGE_END: u16 = 0xFFFF;
+
+pub const PRG_RAM: u16 = 0x6000;
+pub const PRG_RAM_END: u16 = 0x7FFF;
+
 pub const PROGRAM: u16 = 0x8000;
 pub const PROGRAM_START: u16 = 0xFFFC;
 pub const PROGRAM_END: u16 = 0xFFFF;
@@ -51,8 +161,8 @@ pub const STACK: u16 = 0x0100;
 pub const STACK_SIZE: u8 = 0xFF;
 
 impl Mem for Bus {
-    fn mem_read(&mut self, mut addr: u16) -> u8 {
-        match addr {
+    fn mem_read(&mut self, addr: u16) -> u8 {
+        let data = match addr {
             // RAM
             RAM..=RAM_MIRRORS_END => {
                 let mirror_down_addr = addr & 0b0111_1111_1111;
@@ -60,9 +170,8 @@ impl Mem for Bus {
             }
 
             // PPU
-            PPUCTRL | PPUMASK | OAMADDR | PPUSCROLL | PPUADDR | OAMDMA => {
-                panic!("Attempt to read from write-only PPU address {:x}", addr);
-            }
+            // Write-only registers don't drive the bus
+            PPUCTRL | PPUMASK | OAMADDR | PPUSCROLL | PPUADDR | OAMDMA => self.open_bus,
             PPUSTATUS => self.ppu.read_status(),
             OAMDATA => self.ppu.read_oam_data(),
             PPUDATA => self.ppu.read_data(),
@@ -71,21 +180,60 @@ impl Mem for Bus {
                 self.mem_read(mirror_down_addr)
             }
 
-            // PROGRAM
-            PROGRAM..=PROGRAM_END => {
-                addr -= PROGRAM;
-                if self.prg_rom.len() == PROGRAM as usize / 2 && addr >= PROGRAM / 2 {
-                    // mirror if needed
-                    addr %= PROGRAM / 2;
-                }
-                self.prg_rom[addr as usize]
+            // APU, the channel registers are write-only and bit 5 of $4015 isn't driven
+            PULSE_1..=APU_REGISTERS_END => self.open_bus,
+            APU_STATUS => (self.open_bus & 0b0010_0000) | self.apu.read_status(),
+
+            // CONTROLLERS
+            JOYPAD1 => self.read_controller(ControllerPort::One),
+            JOYPAD2 => self.read_controller(ControllerPort::Two),
+
+            // APU and I/O test registers, disabled on retail consoles
+            0x4018..=0x401F => self.open_bus,
+
+            // CARTRIDGE
+            CARTRIDGE..=CARTRIDGE_END => self.mapper.borrow_mut().cpu_read(addr),
+        };
+
+        self.open_bus = data;
+        data
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        match addr {
+            // RAM
+            RAM..=RAM_MIRRORS_END => {
+                let mirror_down_addr = addr & 0b0111_1111_1111;
+                self.cpu_vram[mirror_down_addr as usize]
             }
 
-            _ => panic!("Unhandled read of memory"),
+            // PPU
+            PPUSTATUS => self.ppu.peek_status(),
+            OAMDATA => self.ppu.peek_oam_data(),
+            PPUDATA => self.ppu.peek_data(),
+            PPU_REGISTERS..=PPU_REGISTERS_MIRRORS_END => {
+                let mirror_down_addr = addr & 0b0010_0000_0000_0111;
+                self.peek(mirror_down_addr)
+            }
+
+            // APU
+            APU_STATUS => (self.open_bus & 0b0010_0000) | self.apu.peek_status(),
+
+            // CONTROLLERS
+            JOYPAD1 => self.peek_controller(ControllerPort::One),
+            JOYPAD2 => self.peek_controller(ControllerPort::Two),
+
+            // CARTRIDGE
+            CARTRIDGE..=CARTRIDGE_END => self.mapper.borrow().cpu_peek(addr),
+
+            // Write-only and unmapped registers
+            _ => self.open_bus,
         }
     }
 
     fn mem_write(&mut self, addr: u16, data: u8) {
+        self.open_bus = data;
+
         match addr {
             // RAM
             RAM..=RAM_MIRRORS_END => {
@@ -98,7 +246,8 @@ impl Mem for Bus {
                 self.ppu.write_to_ctrl(data);
             }
             PPUMASK => self.ppu.write_to_mask(data),
-            PPUSTATUS => panic!("Attempted to write to PPU Status register"),
+            // Read-only, the write only refreshes the PPU's data latch
+            PPUSTATUS => {}
             OAMADDR => self.ppu.write_to_oam_addr(data),
             OAMDATA => self.ppu.write_to_oam_data(data),
             PPUSCROLL => self.ppu.write_to_scroll(data),
@@ -109,10 +258,174 @@ impl Mem for Bus {
                 self.mem_write(mirror_down_addr, data);
             }
 
-            // PROGRAM
-            PROGRAM..=PROGRAM_END => panic!("Attempted to write to cartridge ROM space"),
+            OAMDMA => {
+                *self.ppu.oamdma = data;
+                self.oam_dma = Some(data);
+            }
+
+            // APU
+            PULSE_1..=APU_REGISTERS_END | APU_STATUS | FRAME_COUNTER => {
+                self.apu.write_register(addr, data)
+            }
+
+            // CONTROLLERS, the strobe goes to both ports
+            JOYPAD1 => {
+                for controller in &mut self.controllers {
+                    controller.write(data);
+                }
+            }
+
+            // CARTRIDGE
+            CARTRIDGE..=CARTRIDGE_END => self.mapper.borrow_mut().cpu_write(addr, data),
 
-            _ => panic!("Unhandled write of memory"),
+            // Disabled APU and I/O test registers at $4018-$401F
+            _ => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::test_rom;
+
+    use super::*;
+
+    #[test]
+    fn peek_has_no_side_effects() {
+        let mut bus = Bus::new(test_rom(&[0xAA])).unwrap();
+        bus.ppu.status.set_vblank_status(true);
+        bus.mem_write(PPUADDR, 0x21);
+        bus.mem_write(PPUADDR, 0x00);
+
+        assert_eq!(bus.peek(PPUSTATUS), 0x80);
+        assert!(bus.ppu.status.is_in_vblank());
+
+        bus.peek(PPUDATA);
+        assert_eq!(bus.ppu.internal.v.get(), 0x2100);
+
+        assert_eq!(bus.peek(0x8000), 0xAA);
+    }
+
+    #[test]
+    fn peek_write_only_registers() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+        bus.mem_write(0x0000, 0x40);
+        bus.mem_read(0x0000);
+
+        assert_eq!(bus.peek(PPUCTRL), 0x40);
+        assert_eq!(bus.peek(OAMDMA), 0x40);
+        assert_eq!(bus.peek(0x4018), 0x40);
+    }
+
+    #[test]
+    fn controller_ports() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+        bus.set_buttons(ControllerPort::One, JoypadButton::A);
+        bus.set_buttons(ControllerPort::Two, JoypadButton::B);
+
+        bus.mem_write(JOYPAD1, 1);
+        bus.mem_write(JOYPAD1, 0);
+        assert_eq!(bus.mem_read(JOYPAD1) & 1, 1);
+        assert_eq!(bus.mem_read(JOYPAD2) & 1, 0);
+        assert_eq!(bus.mem_read(JOYPAD1) & 1, 0);
+        assert_eq!(bus.mem_read(JOYPAD2) & 1, 1);
+    }
+
+    #[test]
+    fn controller_open_bus() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+        bus.set_buttons(ControllerPort::One, JoypadButton::A);
+        bus.mem_write(JOYPAD1, 1);
+
+        // LDA $4016 leaves the high byte of the operand on the bus
+        bus.mem_read(0x8002);
+        bus.mem_write(0x0000, 0x40);
+        bus.mem_read(0x0000);
+        assert_eq!(bus.peek(JOYPAD1), 0x41);
+        assert_eq!(bus.mem_read(JOYPAD1), 0x41);
+    }
+
+    #[test]
+    fn unplugged() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+        bus.connect(ControllerPort::Two, Box::new(Unplugged));
+        bus.set_buttons(ControllerPort::Two, JoypadButton::all());
+        bus.mem_write(JOYPAD1, 1);
+
+        assert_eq!(bus.mem_read(JOYPAD2), 0);
+    }
+
+    #[test]
+    fn prg_ram() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+
+        bus.mem_write(PRG_RAM, 0x12);
+        bus.mem_write(PRG_RAM_END, 0x34);
+        assert_eq!(bus.mem_read(PRG_RAM), 0x12);
+        assert_eq!(bus.peek(PRG_RAM_END), 0x34);
+    }
+
+    #[test]
+    fn apu_registers() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+
+        for addr in PULSE_1..=APU_REGISTERS_END {
+            bus.mem_write(addr, 0);
+        }
+        bus.mem_write(APU_STATUS, 0b0000_0001);
+        bus.mem_write(0x4003, 0b0000_1000);
+
+        assert_eq!(bus.mem_read(APU_STATUS), 0b0000_0001);
+    }
+
+    #[test]
+    fn write_only_registers_are_open_bus() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+        bus.mem_write(0x0000, 0x40);
+        bus.mem_read(0x0000);
+
+        for addr in [
+            PPUCTRL, PPUMASK, OAMADDR, PPUSCROLL, PPUADDR, 0x3FF8, 0x4018, 0x401F,
+        ] {
+            assert_eq!(bus.mem_read(addr), 0x40);
+        }
+    }
+
+    #[test]
+    fn unmapped_writes_are_ignored() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+
+        for addr in [PPUSTATUS, 0x3FFA, 0x4018, 0x401F] {
+            bus.mem_write(addr, 0xFF);
+        }
+        assert_eq!(bus.ppu.read_status() & 0b1000_0000, 0);
+    }
+
+    #[test]
+    fn apu_registers_are_open_bus() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+        bus.mem_write(0x0000, 0x40);
+        bus.mem_read(0x0000);
+
+        for addr in PULSE_1..=OAMDMA {
+            assert_eq!(bus.mem_read(addr), 0x40);
+        }
+    }
+
+    #[test]
+    fn frame_irq() {
+        let mut bus = Bus::new(test_rom(&[])).unwrap();
+
+        while !bus.irq() {
+            bus.tick(1);
+        }
+        assert_eq!(bus.irq_sources(), IrqSource::FRAME_COUNTER);
+        assert_eq!(bus.cycles, 7 + 29828);
+
+        // Reading $4015 acknowledges it, inhibiting it with $4017 keeps it from coming back
+        assert_eq!(bus.mem_read(APU_STATUS) & 0b0100_0000, 0b0100_0000);
+        bus.mem_write(FRAME_COUNTER, 0b0100_0000);
+        bus.tick(3);
+        assert!(!bus.irq());
+    }
+}
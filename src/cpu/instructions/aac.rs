@@ -7,7 +7,7 @@ pub const AAC_IMMEDIATE2: u8 = 0x2B;
 
 /// A logical AND is performed, bit by bit, on the accumulator contents using the contents of a byte of memory.
 /// If the result is negative then the carry flag is set.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionAAC {
     and: InstructionAND,
 }
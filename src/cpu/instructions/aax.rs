@@ -7,7 +7,7 @@ pub const AAX_INDIRECTX: u8 = 0x83;
 
 /// A logical AND is performed, bit by bit, on the accumulator contents using the contents of the X register.
 /// The result is stored in memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionAAX {
     pub addr: u16,
     pub addressing_mode: AddressingMode,
@@ -11,7 +11,7 @@ pub const ADC_INDIRECTY: u8 = 0x71;
 
 /// This instruction adds the contents of a memory location to the accumulator together with the carry bit.
 /// If overflow occurs the carry bit is set, this enables multiple byte addition to be performed.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionADC {
     pub(crate) addr: u16,
     pub(crate) addressing_mode: AddressingMode,
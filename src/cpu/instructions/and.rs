@@ -10,7 +10,7 @@ pub const AND_INDIRECTX: u8 = 0x21;
 pub const AND_INDIRECTY: u8 = 0x31;
 
 /// A logical AND is performed, bit by bit, on the accumulator contents using the contents of a byte of memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionAND {
     pub addr: u16,
     pub addressing_mode: AddressingMode,
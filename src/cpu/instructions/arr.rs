@@ -12,7 +12,7 @@ pub const ARR_IMMEDIATE: u8 = 0x6B;
 /// |  1  |  0  |->|  0  |  1  |
 /// |  0  |  1  |->|  1  |  1  |
 /// |  0  |  0  |->|  0  |  0  |
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionARR {
     and: InstructionAND,
     ror: InstructionROR,
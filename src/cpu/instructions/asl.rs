@@ -1,4 +1,4 @@
-use crate::{AddressingMode, Instruction, Mem, OpCode, Status, CPU};
+use crate::{AddressingMode, Instruction, OpCode, Status, CPU};
 
 pub const ASL_ACCUMULATOR: u8 = 0x0A;
 pub const ASL_ZEROPAGE: u8 = 0x06;
@@ -9,16 +9,28 @@ pub const ASL_ABSOLUTEX: u8 = 0x1E;
 /// This operation shifts all the bits of the accumulator or memory contents one bit left.
 /// Bit 0 is set to 0 and bit 7 is placed in the carry flag.
 /// The effect of this operation is to multiply the memory contents by 2 (ignoring 2's complement considerations), setting the carry if the result will not fit in 8 bits.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionASL {
     pub(crate) addr: Option<u16>,
     pub(crate) addressing_mode: AddressingMode,
 }
 
+impl InstructionASL {
+    pub(crate) fn shift(cpu: &mut CPU, value: u8) -> u8 {
+        let shifted = (value as u16) << 1;
+
+        cpu.status.set(Status::CARRY, shifted > u8::MAX as u16);
+
+        let shifted = shifted as u8;
+
+        cpu.update_zero_and_negative_flags(shifted);
+        shifted
+    }
+}
+
 impl OpCode for InstructionASL {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let addr = (cpu.mem_read(cpu.program_counter) != ASL_ACCUMULATOR)
-            .then(|| cpu.get_operand_address().0);
+        let addr = (cpu.opcode != ASL_ACCUMULATOR).then(|| cpu.get_operand_address().0);
 
         Instruction::ASL(Self {
             addr,
@@ -27,27 +39,14 @@ impl OpCode for InstructionASL {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        let value = self
-            .addr
-            .map(|addr| cpu.mem_read(addr))
-            .unwrap_or(cpu.register_a);
-
-        let shifted = (value as u16) << 1;
-
-        cpu.status.set(Status::CARRY, shifted > u8::MAX as u16);
-
-        let shifted = shifted as u8;
-
         match self.addr {
             Some(addr) => {
-                cpu.mem_write(addr, shifted);
+                cpu.read_modify_write(addr, Self::shift);
             }
             None => {
-                cpu.register_a = shifted;
+                cpu.register_a = Self::shift(cpu, cpu.register_a);
             }
         }
-
-        cpu.update_zero_and_negative_flags(shifted);
     }
 
     fn cycles(&self) -> u8 {
@@ -66,7 +65,7 @@ mod tests {
     mod asl {
         use test_case::test_case;
 
-        use crate::instructions::BRK;
+        use crate::{instructions::BRK, Mem};
 
         use super::super::*;
 
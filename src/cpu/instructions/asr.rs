@@ -5,7 +5,7 @@ use super::{InstructionAND, InstructionLSR};
 pub const ASR_IMMEDIATE: u8 = 0x4B;
 
 /// Perform AND and LSR.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionASR {
     and: InstructionAND,
     lsr: InstructionLSR,
@@ -6,7 +6,7 @@ pub const ATX_IMMEDIATE: u8 = 0xAB;
 
 /// A logical AND is performed, bit by bit, on the accumulator contents using the contents of a byte of memory,
 /// then copy the value of the accumulator to the X register.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionATX {
     and: InstructionAND,
     tax: InstructionTAX,
@@ -5,7 +5,7 @@ pub const AXA_INDIRECTY: u8 = 0x93;
 
 /// A logical AND is performed, bit by bit, on the accumulator contents using the contents of the register X,
 /// then AND the result with 7 and store it in memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionAXA {
     addr: u16,
     addressing_mode: AddressingMode,
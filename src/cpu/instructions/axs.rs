@@ -4,7 +4,7 @@ pub const AXS_IMMEDIATE: u8 = 0xCB;
 
 /// A logical AND is performed, bit by bit, on the register X contents using the contents of the accumulator,
 /// then sutract the memory byte from the X register.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionAXS {
     addr: u16,
 }
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const BCC: u8 = 0x90;
 
 /// If the carry flag is clear then add the relative displacement to the program counter to cause a branch to a new location.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionBCC {
     target: u16,
     condition: bool,
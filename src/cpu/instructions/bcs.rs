@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const BCS: u8 = 0xB0;
 
 /// If the carry flag is set then add the relative displacement to the program counter to cause a branch to a new location.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionBCS {
     target: u16,
     condition: bool,
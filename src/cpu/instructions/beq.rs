@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const BEQ: u8 = 0xF0;
 
 /// If the zero flag is set then add the relative displacement to the program counter to cause a branch to a new location.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionBEQ {
     target: u16,
     condition: bool,
@@ -6,7 +6,7 @@ pub const BIT_ABSOLUTE: u8 = 0x2C;
 /// This instructions is used to test if one or more bits are set in a target memory location.
 /// The mask pattern in A is ANDed with the value in memory to set or clear the zero flag, but the result is not kept.
 /// Bits 7 and 6 of the value from memory are copied into the N and V flags.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionBIT {
     addr: u16,
     addressing_mode: AddressingMode,
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const BMI: u8 = 0x30;
 
 /// If the negative flag is set then add the relative displacement to the program counter to cause a branch to a new location.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionBMI {
     target: u16,
     condition: bool,
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const BNE: u8 = 0xD0;
 
 /// If the zero flag is clear then add the relative displacement to the program counter to cause a branch to a new location.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionBNE {
     target: u16,
     condition: bool,
@@ -1,10 +1,10 @@
-use crate::{Instruction, OpCode, Status, CPU};
+use crate::{Instruction, Interrupt, OpCode, CPU};
 
 pub const BRK: u8 = 0x00;
 
 /// The BRK instruction forces the generation of an interrupt request.
 /// The program counter and processor status are pushed on the stack then the IRQ interrupt vector at $FFFE/F is loaded into the PC and the break flag in the status set to one.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionBRK;
 
 impl OpCode for InstructionBRK {
@@ -13,37 +13,65 @@ impl OpCode for InstructionBRK {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        cpu.stack_push_u16(cpu.program_counter);
-        cpu.stack_push(cpu.status.bits());
-        cpu.status.insert(Status::BREAK_COMMAND);
+        // BRK is followed by a padding byte that is skipped on return
+        cpu.program_counter = cpu.program_counter.wrapping_add(1);
+        cpu.interrupt(Interrupt::BRK);
     }
 
     fn cycles(&self) -> u8 {
-        7
+        Interrupt::BRK.cpu_cycles
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::{Status, PROGRAM};
+    use crate::{instructions::NOP, Mem, Status, PROGRAM};
 
     use super::*;
 
+    fn vectored_rom(vector: u16, handler: u16) -> Vec<u8> {
+        let mut program = vec![NOP, BRK];
+        program.resize(0x8000, 0);
+        let [lo, hi] = handler.to_le_bytes();
+        program[(vector - PROGRAM) as usize] = lo;
+        program[(vector - PROGRAM + 1) as usize] = hi;
+        program
+    }
+
     #[test]
     fn brk() {
         // Setup
-        let mut cpu = CPU::new_test(&[BRK]);
+        let mut cpu = CPU::new_test(&vectored_rom(0xFFFE, 0x1234));
 
         // Break
         cpu.run();
         let status = cpu.stack_pull();
         assert_eq!(
             Status::from_bits_retain(status),
-            Status::UNUSED | Status::INTERRUPT_DISABLE
+            Status::UNUSED | Status::INTERRUPT_DISABLE | Status::BREAK_COMMAND
         );
         let program_counter = cpu.stack_pull_u16();
-        assert_eq!(program_counter, PROGRAM + 1);
-        assert!(cpu.status.contains(Status::BREAK_COMMAND));
+        assert_eq!(program_counter, PROGRAM + 3);
+        assert_eq!(cpu.program_counter, 0x1234);
+        assert!(!cpu.status.contains(Status::BREAK_COMMAND));
+    }
+
+    #[test]
+    fn nmi_hijack() {
+        // Setup
+        let mut program = vectored_rom(0xFFFE, 0x1234);
+        program[0x7FFA] = 0x78;
+        program[0x7FFB] = 0x56;
+        let mut cpu = CPU::new_test(&program);
+        cpu.mem_write(0x2000, 0b1000_0000);
+        cpu.bus.ppu.scanline = 240;
+        cpu.bus.ppu.cycles = 330;
+
+        // NMI asserted during the BRK
+        cpu.run();
+        assert_eq!(cpu.program_counter, 0x5678);
+        let status = cpu.stack_pull();
+        assert!(Status::from_bits_retain(status).contains(Status::BREAK_COMMAND));
     }
 }
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const CLC: u8 = 0x18;
 
 /// Set the carry flag to zero.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionCLC;
 
 impl OpCode for InstructionCLC {
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const CLD: u8 = 0xD8;
 
 /// Sets the decimal mode flag to zero.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionCLD;
 
 impl OpCode for InstructionCLD {
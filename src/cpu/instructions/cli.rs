@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const CLI: u8 = 0x58;
 
 /// Clears the interrupt disable flag allowing normal interrupt requests to be serviced.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionCLI;
 
 impl OpCode for InstructionCLI {
@@ -22,7 +22,10 @@ impl OpCode for InstructionCLI {
 
 #[cfg(test)]
 mod tests {
-    use crate::instructions::BRK;
+    use crate::{
+        instructions::{BRK, INX},
+        IrqSource, PROGRAM,
+    };
 
     use super::*;
 
@@ -31,6 +34,22 @@ mod tests {
         let mut cpu = CPU::new_test(&[CLI, BRK]);
         cpu.status.insert(Status::INTERRUPT_DISABLE);
         cpu.run();
-        assert!(!cpu.status.contains(Status::INTERRUPT_DISABLE))
+        let status = Status::from_bits_retain(cpu.stack_pull()); // BRK Status
+        assert!(!status.contains(Status::INTERRUPT_DISABLE))
+    }
+
+    #[test]
+    fn irq_latency() {
+        // Setup
+        let mut program = vec![CLI, INX, INX, BRK];
+        program.resize(0x8000, 0);
+        program[0x7FFE] = 0x10;
+        program[0x7FFF] = (PROGRAM >> 8) as u8;
+        let mut cpu = CPU::new_test(&program);
+        cpu.bus.set_irq(IrqSource::EXTERNAL, true);
+
+        // The IRQ is taken one instruction after CLI
+        cpu.run();
+        assert_eq!(cpu.register_x, 1);
     }
 }
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const CLV: u8 = 0xB8;
 
 /// Clears the overflow flag.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionCLV;
 
 impl OpCode for InstructionCLV {
@@ -10,7 +10,7 @@ pub const CMP_INDIRECTX: u8 = 0xC1;
 pub const CMP_INDIRECTY: u8 = 0xD1;
 
 /// This instruction compares the contents of the accumulator with another memory held value and sets the zero and carry flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionCMP {
     addr: u16,
     addressing_mode: AddressingMode,
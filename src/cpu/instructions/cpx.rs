@@ -5,7 +5,7 @@ pub const CPX_ZEROPAGE: u8 = 0xE4;
 pub const CPX_ABSOLUTE: u8 = 0xEC;
 
 /// This instruction compares the contents of the X register with another memory held value and sets the zero and carry flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionCPX {
     addr: u16,
     addressing_mode: AddressingMode,
@@ -5,7 +5,7 @@ pub const CPY_ZEROPAGE: u8 = 0xC4;
 pub const CPY_ABSOLUTE: u8 = 0xCC;
 
 /// This instruction compares the contents of the Y register with another memory held value and sets the zero and carry flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionCPY {
     addr: u16,
     addressing_mode: AddressingMode,
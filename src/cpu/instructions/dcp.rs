@@ -1,4 +1,4 @@
-use crate::{AddressingMode, Instruction, Mem, OpCode, CPU};
+use crate::{AddressingMode, Instruction, OpCode, CPU};
 
 pub const DCP_ZEROPAGE: u8 = 0xC7;
 pub const DCP_ZEROPAGEX: u8 = 0xD7;
@@ -9,7 +9,7 @@ pub const DCP_INDIRECTX: u8 = 0xC3;
 pub const DCP_INDIRECTY: u8 = 0xD3;
 
 /// Subtracts one to the value held at a specified memory location and compares it with the accumulator.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionDCP {
     pub addr: u16,
     pub addressing_mode: AddressingMode,
@@ -24,8 +24,7 @@ impl OpCode for InstructionDCP {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        let result = cpu.mem_read(self.addr).wrapping_sub(1);
-        cpu.mem_write(self.addr, result);
+        let result = cpu.read_modify_write(self.addr, |_, value| value.wrapping_sub(1));
         cpu.compare(result, cpu.register_a);
     }
 
@@ -44,7 +43,7 @@ impl OpCode for InstructionDCP {
 mod tests {
     use test_case::test_case;
 
-    use crate::{instructions::BRK, Status};
+    use crate::{instructions::BRK, Mem, Status};
 
     use super::*;
 
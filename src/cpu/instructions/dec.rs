@@ -1,4 +1,4 @@
-use crate::{AddressingMode, Instruction, Mem, OpCode, CPU};
+use crate::{AddressingMode, Instruction, OpCode, CPU};
 
 pub const DEC_ZEROPAGE: u8 = 0xC6;
 pub const DEC_ZEROPAGEX: u8 = 0xD6;
@@ -6,7 +6,7 @@ pub const DEC_ABSOLUTE: u8 = 0xCE;
 pub const DEC_ABSOLUTEX: u8 = 0xDE;
 
 /// Subtracts one from the value held at a specified memory location setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionDEC {
     addr: u16,
     addressing_mode: AddressingMode,
@@ -21,9 +21,11 @@ impl OpCode for InstructionDEC {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        let result = cpu.mem_read(self.addr).wrapping_sub(1);
-        cpu.mem_write(self.addr, result);
-        cpu.update_zero_and_negative_flags(result);
+        cpu.read_modify_write(self.addr, |cpu, value| {
+            let result = value.wrapping_sub(1);
+            cpu.update_zero_and_negative_flags(result);
+            result
+        });
     }
 
     fn cycles(&self) -> u8 {
@@ -40,7 +42,7 @@ impl OpCode for InstructionDEC {
 mod tests {
     use test_case::test_case;
 
-    use crate::{instructions::BRK, Status};
+    use crate::{instructions::BRK, Mem, Status};
 
     use super::*;
 
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const DEX: u8 = 0xCA;
 
 /// Subtracts one from the X register setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionDEX;
 
 impl OpCode for InstructionDEX {
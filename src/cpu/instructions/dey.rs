@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const DEY: u8 = 0x88;
 
 /// Subtracts one from the Y register setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionDEY;
 
 impl OpCode for InstructionDEY {
@@ -10,7 +10,7 @@ pub const EOR_INDIRECTX: u8 = 0x41;
 pub const EOR_INDIRECTY: u8 = 0x51;
 
 /// An exclusive OR is performed, bit by bit, on the accumulator contents using the contents of a byte of memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionEOR {
     pub(crate) addr: u16,
     pub(crate) addressing_mode: AddressingMode,
@@ -1,4 +1,4 @@
-use crate::{AddressingMode, Instruction, Mem, OpCode, CPU};
+use crate::{AddressingMode, Instruction, OpCode, CPU};
 
 pub const INC_ZEROPAGE: u8 = 0xE6;
 pub const INC_ZEROPAGEX: u8 = 0xF6;
@@ -6,12 +6,20 @@ pub const INC_ABSOLUTE: u8 = 0xEE;
 pub const INC_ABSOLUTEX: u8 = 0xFE;
 
 /// Adds one to the value held at a specified memory location setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionINC {
     pub(crate) addr: u16,
     pub(crate) addressing_mode: AddressingMode,
 }
 
+impl InstructionINC {
+    pub(crate) fn increment(cpu: &mut CPU, value: u8) -> u8 {
+        let result = value.wrapping_add(1);
+        cpu.update_zero_and_negative_flags(result);
+        result
+    }
+}
+
 impl OpCode for InstructionINC {
     fn fetch(cpu: &mut CPU) -> Instruction {
         Instruction::INC(Self {
@@ -21,9 +29,7 @@ impl OpCode for InstructionINC {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        let result = cpu.mem_read(self.addr).wrapping_add(1);
-        cpu.mem_write(self.addr, result);
-        cpu.update_zero_and_negative_flags(result);
+        cpu.read_modify_write(self.addr, Self::increment);
     }
 
     fn cycles(&self) -> u8 {
@@ -40,7 +46,7 @@ impl OpCode for InstructionINC {
 mod tests {
     use test_case::test_case;
 
-    use crate::{instructions::BRK, Status};
+    use crate::{instructions::BRK, Mem, Status};
 
     use super::*;
 
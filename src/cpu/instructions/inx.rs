@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const INX: u8 = 0xE8;
 
 /// Adds one to the X register setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionINX;
 
 impl OpCode for InstructionINX {
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const INY: u8 = 0xC8;
 
 /// Adds one to the Y register setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionINY;
 
 impl OpCode for InstructionINY {
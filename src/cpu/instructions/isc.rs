@@ -1,6 +1,6 @@
 use crate::{AddressingMode, Instruction, OpCode, CPU};
 
-use super::{InstructionINC, InstructionSBC};
+use super::InstructionINC;
 
 pub const ISC_ZEROPAGE: u8 = 0xE7;
 pub const ISC_ZEROPAGEX: u8 = 0xF7;
@@ -14,34 +14,23 @@ pub const ISC_INDIRECTY: u8 = 0xF3;
 /// then subtracts the result to the accumulator together with the not of the carry bit.
 /// If overflow occurs the carry bit is clear, this enables multiple byte subtraction to be performed,
 /// and setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionISC {
-    inc: InstructionINC,
-    sbc: InstructionSBC,
+    addr: u16,
     addressing_mode: AddressingMode,
 }
 
 impl OpCode for InstructionISC {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let (addr, page_crossed) = cpu.get_operand_address();
-        let addressing_mode = cpu.get_addressing_mode();
         Instruction::ISB(Self {
-            inc: InstructionINC {
-                addr,
-                addressing_mode,
-            },
-            sbc: InstructionSBC {
-                addr,
-                addressing_mode,
-                page_crossed,
-            },
-            addressing_mode,
+            addr: cpu.get_operand_address().0,
+            addressing_mode: cpu.get_addressing_mode(),
         })
     }
 
     fn execute(self, cpu: &mut CPU) {
-        self.inc.execute(cpu);
-        self.sbc.execute(cpu);
+        let value = cpu.read_modify_write(self.addr, InstructionINC::increment);
+        cpu.sum((value as i8).wrapping_neg().wrapping_sub(1) as u8);
     }
 
     fn cycles(&self) -> u8 {
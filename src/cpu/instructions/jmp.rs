@@ -4,7 +4,7 @@ pub const JMP_ABSOLUTE: u8 = 0x4C;
 pub const JMP_INDIRECT: u8 = 0x6C;
 
 /// Sets the program counter to the address specified by the operand.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionJMP {
     addr: u16,
     addressing_mode: AddressingMode,
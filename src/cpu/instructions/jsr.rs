@@ -1,23 +1,27 @@
-use crate::{Instruction, OpCode, CPU};
+use crate::{Instruction, Mem, OpCode, CPU};
 
 pub const JSR: u8 = 0x20;
 
 /// The JSR instruction pushes the address (minus one) of the return point on to the stack and then sets the program counter to the target memory address.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionJSR {
-    addr: u16,
+    lo: u8,
 }
 
 impl OpCode for InstructionJSR {
     fn fetch(cpu: &mut CPU) -> Instruction {
+        // The high byte of the target is only read after the return address has been pushed
         Instruction::JSR(Self {
-            addr: cpu.get_operand_address().0,
+            lo: cpu.mem_read(cpu.program_counter.wrapping_add(1)),
         })
     }
 
     fn execute(self, cpu: &mut CPU) {
-        cpu.stack_push_u16(cpu.program_counter.wrapping_sub(1));
-        cpu.program_counter = self.addr;
+        cpu.stack_dummy_read();
+        let hi_addr = cpu.program_counter.wrapping_sub(1);
+        cpu.stack_push_u16(hi_addr);
+        let hi = cpu.mem_read(hi_addr);
+        cpu.program_counter = u16::from_le_bytes([self.lo, hi]);
     }
 
     fn cycles(&self) -> u8 {
@@ -14,7 +14,7 @@ pub const KIL_IMPLIED11: u8 = 0xD2;
 pub const KIL_IMPLIED12: u8 = 0xF2;
 
 /// Stop program counter (processor lock up).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionKIL;
 
 impl OpCode for InstructionKIL {
@@ -22,12 +22,13 @@ impl OpCode for InstructionKIL {
         Instruction::JAM(Self)
     }
 
-    fn execute(self, _cpu: &mut CPU) {
-        // TODO: handle halt
+    fn execute(self, cpu: &mut CPU) {
+        cpu.jammed = true;
     }
 
     fn cycles(&self) -> u8 {
-        0
+        // Opcode fetch and the read of the next byte before locking up
+        2
     }
 }
 
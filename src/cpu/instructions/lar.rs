@@ -5,7 +5,7 @@ pub const LAR_ABSOLUTEY: u8 = 0xBB;
 /// A logical AND is performed, bit by bit, on the stack pointer contents using the contents of a byte of memory,
 /// transfer the result to the accumulator, X register and stack pointer,
 /// setting the zero and negative flags as appropriate
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionLAR {
     addr: u16,
     page_crossed: bool,
@@ -1,6 +1,4 @@
-use crate::{AddressingMode, Instruction, OpCode, CPU};
-
-use super::{InstructionLDA, InstructionLDX};
+use crate::{AddressingMode, Instruction, Mem, OpCode, CPU};
 
 pub const LAX_ZEROPAGE: u8 = 0xA7;
 pub const LAX_ZEROPAGEY: u8 = 0xB7;
@@ -10,10 +8,9 @@ pub const LAX_INDIRECTX: u8 = 0xA3;
 pub const LAX_INDIRECTY: u8 = 0xB3;
 
 /// Performs LDA and LDX.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionLAX {
-    lda: InstructionLDA,
-    ldx: InstructionLDX,
+    addr: u16,
     page_crossed: bool,
     addressing_mode: AddressingMode,
 }
@@ -23,24 +20,18 @@ impl OpCode for InstructionLAX {
         let (addr, page_crossed) = cpu.get_operand_address();
         let addressing_mode = cpu.get_addressing_mode();
         Instruction::LAX(Self {
-            lda: InstructionLDA {
-                addr,
-                addressing_mode,
-                page_crossed,
-            },
-            ldx: InstructionLDX {
-                addr,
-                addressing_mode,
-                page_crossed,
-            },
+            addr,
             page_crossed,
             addressing_mode,
         })
     }
 
     fn execute(self, cpu: &mut CPU) {
-        self.lda.execute(cpu);
-        self.ldx.execute(cpu);
+        // A single read loads both registers
+        let data = cpu.mem_read(self.addr);
+        cpu.register_a = data;
+        cpu.register_x = data;
+        cpu.update_zero_and_negative_flags(data);
     }
 
     fn cycles(&self) -> u8 {
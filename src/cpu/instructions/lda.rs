@@ -10,7 +10,7 @@ pub const LDA_INDIRECTX: u8 = 0xA1;
 pub const LDA_INDIRECTY: u8 = 0xB1;
 
 /// Loads a byte of memory into the accumulator setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionLDA {
     pub(crate) addr: u16,
     pub(crate) addressing_mode: AddressingMode,
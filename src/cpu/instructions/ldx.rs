@@ -7,7 +7,7 @@ pub const LDX_ABSOLUTE: u8 = 0xAE;
 pub const LDX_ABSOLUTEY: u8 = 0xBE;
 
 /// Loads a byte of memory into the X register setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionLDX {
     pub(crate) addr: u16,
     pub(crate) addressing_mode: AddressingMode,
@@ -7,7 +7,7 @@ pub const LDY_ABSOLUTE: u8 = 0xAC;
 pub const LDY_ABSOLUTEX: u8 = 0xBC;
 
 /// Loads a byte of memory into the Y register setting the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionLDY {
     addr: u16,
     addressing_mode: AddressingMode,
@@ -1,4 +1,4 @@
-use crate::{AddressingMode, Instruction, Mem, OpCode, Status, CPU};
+use crate::{AddressingMode, Instruction, OpCode, Status, CPU};
 
 pub const LSR_ACCUMULATOR: u8 = 0x4A;
 pub const LSR_ZEROPAGE: u8 = 0x46;
@@ -9,16 +9,26 @@ pub const LSR_ABSOLUTEX: u8 = 0x5E;
 /// Each of the bits in A or M is shift one place to the right.
 /// The bit that was in bit 0 is shifted into the carry flag.
 /// Bit 7 is set to zero.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionLSR {
     pub(crate) addr: Option<u16>,
     pub(crate) addressing_mode: AddressingMode,
 }
 
+impl InstructionLSR {
+    pub(crate) fn shift(cpu: &mut CPU, value: u8) -> u8 {
+        cpu.status.set(Status::CARRY, value & 1 != 0);
+
+        let shifted = value >> 1;
+
+        cpu.update_zero_and_negative_flags(shifted);
+        shifted
+    }
+}
+
 impl OpCode for InstructionLSR {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let addr = (cpu.mem_read(cpu.program_counter) != LSR_ACCUMULATOR)
-            .then(|| cpu.get_operand_address().0);
+        let addr = (cpu.opcode != LSR_ACCUMULATOR).then(|| cpu.get_operand_address().0);
 
         Instruction::LSR(Self {
             addr,
@@ -27,25 +37,14 @@ impl OpCode for InstructionLSR {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        let value = self
-            .addr
-            .map(|addr| cpu.mem_read(addr))
-            .unwrap_or(cpu.register_a);
-
-        cpu.status.set(Status::CARRY, value & 1 != 0);
-
-        let shifted = value >> 1;
-
         match self.addr {
             Some(addr) => {
-                cpu.mem_write(addr, shifted);
+                cpu.read_modify_write(addr, Self::shift);
             }
             None => {
-                cpu.register_a = shifted;
+                cpu.register_a = Self::shift(cpu, cpu.register_a);
             }
         }
-
-        cpu.update_zero_and_negative_flags(shifted);
     }
 
     fn cycles(&self) -> u8 {
@@ -64,7 +63,7 @@ mod tests {
     mod lsr {
         use test_case::test_case;
 
-        use crate::instructions::BRK;
+        use crate::{instructions::BRK, Mem};
 
         use super::super::*;
 
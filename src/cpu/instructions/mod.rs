@@ -152,11 +152,11 @@ pub use tya::*;
 pub use xaa::*;
 pub use xas::*;
 
-use crate::{Mem, OpCode};
+use crate::OpCode;
 
 use super::CPU;
 
-#[derive(Debug, nes_emulator_macros::Instruction)]
+#[derive(Debug, Clone, nes_emulator_macros::Instruction)]
 pub enum Instruction {
     #[opcode(AAC_IMMEDIATE1 | AAC_IMMEDIATE2)]
     ANC(InstructionAAC),
@@ -339,3 +339,31 @@ pub fn is_unofficial_opcode(opcode: u8) -> bool {
         | SRE_INDIRECTY | SXA_ABSOLUTEY | SYA_ABSOLUTEX | XAA_IMMEDIATE | XAS_ABSOLUTEY
     )
 }
+
+/// Stores and read-modify-write instructions.
+/// With indexed addressing they always spend a cycle reading the address before its high byte is fixed,
+/// where reads only do so when a page is crossed.
+#[rustfmt::skip]
+pub fn is_write_opcode(opcode: u8) -> bool {
+    matches!(
+        opcode,
+        STA_ZEROPAGE | STA_ZEROPAGEX | STA_ABSOLUTE | STA_ABSOLUTEX | STA_ABSOLUTEY
+        | STA_INDIRECTX | STA_INDIRECTY | STX_ZEROPAGE | STX_ZEROPAGEY | STX_ABSOLUTE
+        | STY_ZEROPAGE | STY_ZEROPAGEX | STY_ABSOLUTE | AAX_ZEROPAGE | AAX_ZEROPAGEY
+        | AAX_ABSOLUTE | AAX_INDIRECTX | AXA_ABSOLUTEY | AXA_INDIRECTY | SXA_ABSOLUTEY
+        | SYA_ABSOLUTEX | XAS_ABSOLUTEY | ASL_ZEROPAGE | ASL_ZEROPAGEX | ASL_ABSOLUTE
+        | ASL_ABSOLUTEX | LSR_ZEROPAGE | LSR_ZEROPAGEX | LSR_ABSOLUTE | LSR_ABSOLUTEX
+        | ROL_ZEROPAGE | ROL_ZEROPAGEX | ROL_ABSOLUTE | ROL_ABSOLUTEX | ROR_ZEROPAGE
+        | ROR_ZEROPAGEX | ROR_ABSOLUTE | ROR_ABSOLUTEX | INC_ZEROPAGE | INC_ZEROPAGEX
+        | INC_ABSOLUTE | INC_ABSOLUTEX | DEC_ZEROPAGE | DEC_ZEROPAGEX | DEC_ABSOLUTE
+        | DEC_ABSOLUTEX | SLO_ZEROPAGE | SLO_ZEROPAGEX | SLO_ABSOLUTE | SLO_ABSOLUTEX
+        | SLO_ABSOLUTEY | SLO_INDIRECTX | SLO_INDIRECTY | SRE_ZEROPAGE | SRE_ZEROPAGEX
+        | SRE_ABSOLUTE | SRE_ABSOLUTEX | SRE_ABSOLUTEY | SRE_INDIRECTX | SRE_INDIRECTY
+        | RLA_ZEROPAGE | RLA_ZEROPAGEX | RLA_ABSOLUTE | RLA_ABSOLUTEX | RLA_ABSOLUTEY
+        | RLA_INDIRECTX | RLA_INDIRECTY | RRA_ZEROPAGE | RRA_ZEROPAGEX | RRA_ABSOLUTE
+        | RRA_ABSOLUTEX | RRA_ABSOLUTEY | RRA_INDIRECTX | RRA_INDIRECTY | DCP_ZEROPAGE
+        | DCP_ZEROPAGEX | DCP_ABSOLUTE | DCP_ABSOLUTEX | DCP_ABSOLUTEY | DCP_INDIRECTX
+        | DCP_INDIRECTY | ISC_ZEROPAGE | ISC_ZEROPAGEX | ISC_ABSOLUTE | ISC_ABSOLUTEX
+        | ISC_ABSOLUTEY | ISC_INDIRECTX | ISC_INDIRECTY
+    )
+}
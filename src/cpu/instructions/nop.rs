@@ -1,4 +1,4 @@
-use crate::{Instruction, Mem, OpCode, CPU};
+use crate::{AddressingMode, Instruction, Mem, OpCode, CPU};
 
 pub const DOP_IMMEDIATE1: u8 = 0x80;
 pub const DOP_IMMEDIATE2: u8 = 0x82;
@@ -30,24 +30,36 @@ pub const NOP_IMPLIED5: u8 = 0xDA;
 pub const NOP_IMPLIED6: u8 = 0xFA;
 
 /// The NOP instruction causes no changes to the processor other than the normal incrementing of the program counter to the next instruction.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionNOP {
     opcode: u8,
+    addr: Option<u16>,
     page_cross: bool,
 }
 
 impl OpCode for InstructionNOP {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let opcode = cpu.mem_read(cpu.program_counter);
-        let page_cross = match opcode {
-            TOP_ABSOLUTEX1 | TOP_ABSOLUTEX2 | TOP_ABSOLUTEX3 | TOP_ABSOLUTEX4 | TOP_ABSOLUTEX5
-            | TOP_ABSOLUTEX6 => cpu.get_operand_address().1,
-            _ => false,
+        let opcode = cpu.opcode;
+        let (addr, page_cross) = match cpu.get_addressing_mode() {
+            AddressingMode::Implied => (None, false),
+            _ => {
+                let (addr, page_cross) = cpu.get_operand_address();
+                (Some(addr), page_cross)
+            }
         };
-        Instruction::NOP(Self { opcode, page_cross })
+        Instruction::NOP(Self {
+            opcode,
+            addr,
+            page_cross,
+        })
     }
 
-    fn execute(self, _cpu: &mut CPU) {}
+    fn execute(self, cpu: &mut CPU) {
+        // The operand is still read, only to be ignored
+        if let Some(addr) = self.addr {
+            cpu.mem_read(addr);
+        }
+    }
 
     fn cycles(&self) -> u8 {
         match self.opcode {
@@ -107,13 +119,11 @@ mod tests {
     fn nop(instruction: u8, bytes: u16) {
         let mut cpu = CPU::new_test(&[instruction, BRK]);
         cpu.run();
-        assert_eq!(cpu.program_counter, PROGRAM +1 /* from BRK */ + bytes);
+        cpu.stack_pull(); // BRK Status
+        assert_eq!(cpu.stack_pull_u16(), PROGRAM + 2 /* from BRK */ + bytes);
         assert_eq!(cpu.register_a, 0);
         assert_eq!(cpu.register_x, 0);
         assert_eq!(cpu.register_y, 0);
-        assert_eq!(
-            cpu.status,
-            Status::INTERRUPT_DISABLE | Status::UNUSED | Status::BREAK_COMMAND
-        );
+        assert_eq!(cpu.status, Status::INTERRUPT_DISABLE | Status::UNUSED);
     }
 }
@@ -10,7 +10,7 @@ pub const ORA_INDIRECTX: u8 = 0x01;
 pub const ORA_INDIRECTY: u8 = 0x11;
 
 /// An inclusive OR is performed, bit by bit, on the accumulator contents using the contents of a byte of memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionORA {
     pub(crate) addr: u16,
     pub(crate) addressing_mode: AddressingMode,
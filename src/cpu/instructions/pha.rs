@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const PHA: u8 = 0x48;
 
 /// Pushes a copy of the accumulator on to the stack.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionPHA;
 
 impl OpCode for InstructionPHA {
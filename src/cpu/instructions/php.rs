@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const PHP: u8 = 0x08;
 
 /// Pushes a copy of the status flags on to the stack with the B flag set.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionPHP;
 
 impl OpCode for InstructionPHP {
@@ -4,7 +4,7 @@ pub const PLA: u8 = 0x68;
 
 /// Pulls an 8 bit value from the stack and into the accumulator.
 /// The zero and negative flags are set as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionPLA;
 
 impl OpCode for InstructionPLA {
@@ -13,6 +13,7 @@ impl OpCode for InstructionPLA {
     }
 
     fn execute(self, cpu: &mut CPU) {
+        cpu.stack_dummy_read();
         cpu.register_a = cpu.stack_pull();
         cpu.update_zero_and_negative_flags(cpu.register_a);
     }
@@ -4,7 +4,7 @@ pub const PLP: u8 = 0x28;
 
 /// Pulls an 8 bit value from the stack and into the processor flags.
 /// The flags will take on new states as determined by the value pulled, removing the B flag.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionPLP;
 
 impl OpCode for InstructionPLP {
@@ -13,6 +13,7 @@ impl OpCode for InstructionPLP {
     }
 
     fn execute(self, cpu: &mut CPU) {
+        cpu.stack_dummy_read();
         cpu.status = Status::from_bits_truncate(cpu.stack_pull());
         cpu.status.remove(Status::BREAK_COMMAND);
         cpu.status.insert(Status::UNUSED);
@@ -37,7 +38,7 @@ mod tests {
 
         // Push
         cpu.run();
-        assert_eq!(cpu.status, Status::from_bits_truncate(0b0111_0101));
+        assert_eq!(cpu.status, Status::from_bits_truncate(0b0110_0101));
     }
 
     #[test]
@@ -1,6 +1,6 @@
 use crate::{AddressingMode, Instruction, OpCode, CPU};
 
-use super::{InstructionAND, InstructionROL};
+use super::InstructionROL;
 
 pub const RLA_ZEROPAGE: u8 = 0x27;
 pub const RLA_ZEROPAGEX: u8 = 0x37;
@@ -11,34 +11,24 @@ pub const RLA_INDIRECTX: u8 = 0x23;
 pub const RLA_INDIRECTY: u8 = 0x33;
 
 /// Perfoms ROL and AND.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionRLA {
-    rol: InstructionROL,
-    and: InstructionAND,
+    addr: u16,
     addressing_mode: AddressingMode,
 }
 
 impl OpCode for InstructionRLA {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let (addr, page_crossed) = cpu.get_operand_address();
-        let addressing_mode = cpu.get_addressing_mode();
         Instruction::RLA(Self {
-            rol: InstructionROL {
-                addr: Some(addr),
-                addressing_mode,
-            },
-            and: InstructionAND {
-                addr,
-                addressing_mode,
-                page_crossed,
-            },
-            addressing_mode,
+            addr: cpu.get_operand_address().0,
+            addressing_mode: cpu.get_addressing_mode(),
         })
     }
 
     fn execute(self, cpu: &mut CPU) {
-        self.rol.execute(cpu);
-        self.and.execute(cpu);
+        let value = cpu.read_modify_write(self.addr, InstructionROL::rotate);
+        cpu.register_a &= value;
+        cpu.update_zero_and_negative_flags(cpu.register_a);
     }
 
     fn cycles(&self) -> u8 {
@@ -1,4 +1,4 @@
-use crate::{AddressingMode, Instruction, Mem, OpCode, Status, CPU};
+use crate::{AddressingMode, Instruction, OpCode, Status, CPU};
 
 pub const ROL_ACCUMULATOR: u8 = 0x2A;
 pub const ROL_ZEROPAGE: u8 = 0x26;
@@ -8,16 +8,29 @@ pub const ROL_ABSOLUTEX: u8 = 0x3E;
 
 /// Move each of the bits in either A or M one place to the left.
 /// Bit 0 is filled with the current value of the carry flag whilst the old bit 7 becomes the new carry flag value.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionROL {
     pub(crate) addr: Option<u16>,
     pub(crate) addressing_mode: AddressingMode,
 }
 
+impl InstructionROL {
+    pub(crate) fn rotate(cpu: &mut CPU, value: u8) -> u8 {
+        let bit_zero = cpu.status.contains(Status::CARRY);
+        let carry = value & 0b1000_0000;
+
+        let shifted = value << 1 | bit_zero as u8;
+
+        cpu.status.set(Status::CARRY, carry > 0);
+
+        cpu.update_zero_and_negative_flags(shifted);
+        shifted
+    }
+}
+
 impl OpCode for InstructionROL {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let addr = (cpu.mem_read(cpu.program_counter) != ROL_ACCUMULATOR)
-            .then(|| cpu.get_operand_address().0);
+        let addr = (cpu.opcode != ROL_ACCUMULATOR).then(|| cpu.get_operand_address().0);
 
         Instruction::ROL(Self {
             addr,
@@ -26,30 +39,14 @@ impl OpCode for InstructionROL {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        let value = self
-            .addr
-            .map(|addr| cpu.mem_read(addr))
-            .unwrap_or(cpu.register_a);
-
-        let bit_zero = cpu.status.contains(Status::CARRY);
-        let carry = value & 0b1000_0000;
-
-        let shifted = value << 1 | bit_zero as u8;
-
-        cpu.status.set(Status::CARRY, carry > 0);
-
         match self.addr {
             Some(addr) => {
-                cpu.mem_write(addr, shifted);
-                cpu.update_zero_and_negative_flags(shifted);
+                cpu.read_modify_write(addr, Self::rotate);
             }
             None => {
-                cpu.register_a = shifted;
-                cpu.update_negative_flag(shifted);
+                cpu.register_a = Self::rotate(cpu, cpu.register_a);
             }
         }
-
-        cpu.update_zero_and_negative_flags(shifted);
     }
 
     fn cycles(&self) -> u8 {
@@ -68,7 +65,7 @@ mod tests {
     mod rol {
         use test_case::test_case;
 
-        use crate::instructions::BRK;
+        use crate::{instructions::BRK, Mem};
 
         use super::super::*;
 
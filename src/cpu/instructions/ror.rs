@@ -1,4 +1,4 @@
-use crate::{AddressingMode, Instruction, Mem, OpCode, Status, CPU};
+use crate::{AddressingMode, Instruction, OpCode, Status, CPU};
 
 pub const ROR_ACCUMULATOR: u8 = 0x6A;
 pub const ROR_ZEROPAGE: u8 = 0x66;
@@ -8,16 +8,29 @@ pub const ROR_ABSOLUTEX: u8 = 0x7E;
 
 /// Move each of the bits in either A or M one place to the right.
 /// Bit 7 is filled with the current value of the carry flag whilst the old bit 0 becomes the new carry flag value.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionROR {
     pub(crate) addr: Option<u16>,
     pub(crate) addressing_mode: AddressingMode,
 }
 
+impl InstructionROR {
+    pub(crate) fn rotate(cpu: &mut CPU, value: u8) -> u8 {
+        let bit_seven = cpu.status.contains(Status::CARRY);
+        let carry = value & 1;
+
+        let shifted = value >> 1 | (bit_seven as u8) << 7;
+
+        cpu.status.set(Status::CARRY, carry > 0);
+
+        cpu.update_zero_and_negative_flags(shifted);
+        shifted
+    }
+}
+
 impl OpCode for InstructionROR {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let addr = (cpu.mem_read(cpu.program_counter) != ROR_ACCUMULATOR)
-            .then(|| cpu.get_operand_address().0);
+        let addr = (cpu.opcode != ROR_ACCUMULATOR).then(|| cpu.get_operand_address().0);
 
         Instruction::ROR(Self {
             addr,
@@ -26,30 +39,14 @@ impl OpCode for InstructionROR {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        let value = self
-            .addr
-            .map(|addr| cpu.mem_read(addr))
-            .unwrap_or(cpu.register_a);
-
-        let bit_seven = cpu.status.contains(Status::CARRY);
-        let carry = value & 1;
-
-        let shifted = value >> 1 | (bit_seven as u8) << 7;
-
-        cpu.status.set(Status::CARRY, carry > 0);
-
         match self.addr {
             Some(addr) => {
-                cpu.mem_write(addr, shifted);
-                cpu.update_zero_and_negative_flags(shifted);
+                cpu.read_modify_write(addr, Self::rotate);
             }
             None => {
-                cpu.register_a = shifted;
-                cpu.update_negative_flag(shifted);
+                cpu.register_a = Self::rotate(cpu, cpu.register_a);
             }
         }
-
-        cpu.update_zero_and_negative_flags(shifted);
     }
 
     fn cycles(&self) -> u8 {
@@ -68,7 +65,7 @@ mod tests {
     mod ror {
         use test_case::test_case;
 
-        use crate::instructions::BRK;
+        use crate::{instructions::BRK, Mem};
 
         use super::super::*;
 
@@ -1,6 +1,6 @@
 use crate::{AddressingMode, Instruction, OpCode, CPU};
 
-use super::{InstructionADC, InstructionROR};
+use super::InstructionROR;
 
 pub const RRA_ZEROPAGE: u8 = 0x67;
 pub const RRA_ZEROPAGEX: u8 = 0x77;
@@ -11,34 +11,23 @@ pub const RRA_INDIRECTX: u8 = 0x63;
 pub const RRA_INDIRECTY: u8 = 0x73;
 
 /// Perfoms ROR and ADC.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionRRA {
-    ror: InstructionROR,
-    adc: InstructionADC,
+    addr: u16,
     addressing_mode: AddressingMode,
 }
 
 impl OpCode for InstructionRRA {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let (addr, page_crossed) = cpu.get_operand_address();
-        let addressing_mode = cpu.get_addressing_mode();
         Instruction::RRA(Self {
-            ror: InstructionROR {
-                addr: Some(addr),
-                addressing_mode,
-            },
-            adc: InstructionADC {
-                addr,
-                addressing_mode,
-                page_crossed,
-            },
-            addressing_mode,
+            addr: cpu.get_operand_address().0,
+            addressing_mode: cpu.get_addressing_mode(),
         })
     }
 
     fn execute(self, cpu: &mut CPU) {
-        self.ror.execute(cpu);
-        self.adc.execute(cpu);
+        let value = cpu.read_modify_write(self.addr, InstructionROR::rotate);
+        cpu.sum(value);
     }
 
     fn cycles(&self) -> u8 {
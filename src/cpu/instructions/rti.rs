@@ -4,7 +4,7 @@ pub const RTI: u8 = 0x40;
 
 /// The RTI instruction is used at the end of an interrupt processing routine.
 /// It pulls the processor flags from the stack followed by the program counter.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionRTI;
 
 impl OpCode for InstructionRTI {
@@ -13,6 +13,7 @@ impl OpCode for InstructionRTI {
     }
 
     fn execute(self, cpu: &mut CPU) {
+        cpu.stack_dummy_read();
         cpu.status = Status::from_bits_retain(cpu.stack_pull());
         cpu.status.remove(Status::BREAK_COMMAND);
         cpu.status.insert(Status::UNUSED);
@@ -43,7 +44,7 @@ mod tests {
 
         // Break
         cpu.run();
-        assert_eq!(cpu.stack_pull(), 0b1010_1010);
-        assert_eq!(cpu.status, Status::from_bits_retain(0b1011_1010))
+        assert_eq!(cpu.stack_pull(), 0b1011_1010);
+        assert_eq!(cpu.status, Status::from_bits_retain(0b1010_1110))
     }
 }
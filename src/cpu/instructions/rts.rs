@@ -1,10 +1,10 @@
-use crate::{Instruction, OpCode, CPU};
+use crate::{Instruction, Mem, OpCode, CPU};
 
 pub const RTS: u8 = 0x60;
 
 /// The RTS instruction is used at the end of a subroutine to return to the calling routine.
 /// It pulls the program counter (minus one) from the stack.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionRTS;
 
 impl OpCode for InstructionRTS {
@@ -13,7 +13,11 @@ impl OpCode for InstructionRTS {
     }
 
     fn execute(self, cpu: &mut CPU) {
-        cpu.program_counter = cpu.stack_pull_u16().wrapping_add(1);
+        cpu.stack_dummy_read();
+        let addr = cpu.stack_pull_u16();
+        // The pulled address is read while it is incremented
+        cpu.mem_read(addr);
+        cpu.program_counter = addr.wrapping_add(1);
     }
 
     fn cycles(&self) -> u8 {
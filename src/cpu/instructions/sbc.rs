@@ -12,7 +12,7 @@ pub const SBC_INDIRECTY: u8 = 0xF1;
 
 /// This instruction subtracts the contents of a memory location to the accumulator together with the not of the carry bit.
 /// If overflow occurs the carry bit is clear, this enables multiple byte subtraction to be performed.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSBC {
     pub(crate) addr: u16,
     pub(crate) addressing_mode: AddressingMode,
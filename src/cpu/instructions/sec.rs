@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const SEC: u8 = 0x38;
 
 /// Set the carry flag to one.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSEC;
 
 impl OpCode for InstructionSEC {
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const SED: u8 = 0xF8;
 
 /// Set the decimal mode flag to one.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSED;
 
 impl OpCode for InstructionSED {
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, Status, CPU};
 pub const SEI: u8 = 0x78;
 
 /// Set the interrupt disable flag to one.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSEI;
 
 impl OpCode for InstructionSEI {
@@ -22,7 +22,10 @@ impl OpCode for InstructionSEI {
 
 #[cfg(test)]
 mod tests {
-    use crate::instructions::BRK;
+    use crate::{
+        instructions::{BRK, CLI, INX},
+        IrqSource, PROGRAM,
+    };
 
     use super::*;
 
@@ -32,4 +35,25 @@ mod tests {
         cpu.run();
         assert!(cpu.status.contains(Status::INTERRUPT_DISABLE));
     }
+
+    #[test]
+    fn irq_latency() {
+        // Setup
+        let mut program = vec![CLI, SEI, INX, BRK];
+        program.resize(0x8000, 0);
+        program[0x7FFE] = 0x10;
+        program[0x7FFF] = (PROGRAM >> 8) as u8;
+        let mut cpu = CPU::new_test(&program);
+        cpu.bus.set_irq(IrqSource::EXTERNAL, true);
+
+        // The IRQ is still taken right after SEI
+        cpu.run();
+        assert_eq!(cpu.register_x, 0);
+        cpu.stack_pull(); // BRK Status
+        cpu.stack_pull_u16(); // BRK Program Counter
+        let status = Status::from_bits_retain(cpu.stack_pull());
+        assert!(status.contains(Status::INTERRUPT_DISABLE));
+        assert!(!status.contains(Status::BREAK_COMMAND));
+        assert_eq!(cpu.stack_pull_u16(), PROGRAM + 2);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{AddressingMode, Instruction, OpCode, CPU};
 
-use super::{InstructionASL, InstructionORA};
+use super::InstructionASL;
 
 pub const SLO_ZEROPAGE: u8 = 0x07;
 pub const SLO_ZEROPAGEX: u8 = 0x17;
@@ -11,34 +11,24 @@ pub const SLO_INDIRECTX: u8 = 0x03;
 pub const SLO_INDIRECTY: u8 = 0x13;
 
 /// Perfoms ASL and ORA.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSLO {
-    asl: InstructionASL,
-    ora: InstructionORA,
+    addr: u16,
     addressing_mode: AddressingMode,
 }
 
 impl OpCode for InstructionSLO {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let (addr, page_crossed) = cpu.get_operand_address();
-        let addressing_mode = cpu.get_addressing_mode();
         Instruction::SLO(Self {
-            asl: InstructionASL {
-                addr: Some(addr),
-                addressing_mode,
-            },
-            ora: InstructionORA {
-                addr,
-                addressing_mode,
-                page_crossed,
-            },
-            addressing_mode,
+            addr: cpu.get_operand_address().0,
+            addressing_mode: cpu.get_addressing_mode(),
         })
     }
 
     fn execute(self, cpu: &mut CPU) {
-        self.asl.execute(cpu);
-        self.ora.execute(cpu);
+        let value = cpu.read_modify_write(self.addr, InstructionASL::shift);
+        cpu.register_a |= value;
+        cpu.update_zero_and_negative_flags(cpu.register_a);
     }
 
     fn cycles(&self) -> u8 {
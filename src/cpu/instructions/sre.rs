@@ -1,6 +1,6 @@
 use crate::{AddressingMode, Instruction, OpCode, CPU};
 
-use super::{InstructionEOR, InstructionLSR};
+use super::InstructionLSR;
 
 pub const SRE_ZEROPAGE: u8 = 0x47;
 pub const SRE_ZEROPAGEX: u8 = 0x57;
@@ -11,34 +11,24 @@ pub const SRE_INDIRECTX: u8 = 0x43;
 pub const SRE_INDIRECTY: u8 = 0x53;
 
 /// Perfoms LSR and EOR.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSRE {
-    asl: InstructionLSR,
-    ora: InstructionEOR,
+    addr: u16,
     addressing_mode: AddressingMode,
 }
 
 impl OpCode for InstructionSRE {
     fn fetch(cpu: &mut CPU) -> Instruction {
-        let (addr, page_crossed) = cpu.get_operand_address();
-        let addressing_mode = cpu.get_addressing_mode();
         Instruction::SRE(Self {
-            asl: InstructionLSR {
-                addr: Some(addr),
-                addressing_mode,
-            },
-            ora: InstructionEOR {
-                addr,
-                addressing_mode,
-                page_crossed,
-            },
-            addressing_mode,
+            addr: cpu.get_operand_address().0,
+            addressing_mode: cpu.get_addressing_mode(),
         })
     }
 
     fn execute(self, cpu: &mut CPU) {
-        self.asl.execute(cpu);
-        self.ora.execute(cpu);
+        let value = cpu.read_modify_write(self.addr, InstructionLSR::shift);
+        cpu.register_a ^= value;
+        cpu.update_zero_and_negative_flags(cpu.register_a);
     }
 
     fn cycles(&self) -> u8 {
@@ -9,7 +9,7 @@ pub const STA_INDIRECTX: u8 = 0x81;
 pub const STA_INDIRECTY: u8 = 0x91;
 
 /// Stores the contents of the accumulator into memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSTA {
     addr: u16,
     addressing_mode: AddressingMode,
@@ -5,7 +5,7 @@ pub const STX_ZEROPAGEY: u8 = 0x96;
 pub const STX_ABSOLUTE: u8 = 0x8E;
 
 /// Stores the contents of the X register into memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSTX {
     addr: u16,
     addressing_mode: AddressingMode,
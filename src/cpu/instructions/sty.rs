@@ -5,7 +5,7 @@ pub const STY_ZEROPAGEX: u8 = 0x94;
 pub const STY_ABSOLUTE: u8 = 0x8C;
 
 /// Stores the contents of the Y register into memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSTY {
     addr: u16,
     addressing_mode: AddressingMode,
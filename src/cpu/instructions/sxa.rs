@@ -5,7 +5,7 @@ pub const SXA_ABSOLUTEY: u8 = 0x9E;
 /// A logical AND is performed, bit by bit, on the X register contents
 /// using the contents of the high byte of target address + 1,
 /// storing the result in memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSXA {
     addr: u16,
 }
@@ -5,7 +5,7 @@ pub const SYA_ABSOLUTEX: u8 = 0x9C;
 /// A logical AND is performed, bit by bit, on the Y register contents
 /// using the contents of the high byte of target address + 1,
 /// storing the result in memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionSYA {
     addr: u16,
 }
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const TAX: u8 = 0xAA;
 
 /// Copies the current contents of the accumulator into the X register and sets the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionTAX;
 
 impl OpCode for InstructionTAX {
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const TAY: u8 = 0xA8;
 
 /// Copies the current contents of the accumulator into the Y register and sets the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionTAY;
 
 impl OpCode for InstructionTAY {
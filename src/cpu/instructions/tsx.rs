@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const TSX: u8 = 0xBA;
 
 /// Copies the current contents of the stack register into the X register and sets the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionTSX;
 
 impl OpCode for InstructionTSX {
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const TXA: u8 = 0x8A;
 
 /// Copies the current contents of the X register into the accumulator and sets the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionTXA;
 
 impl OpCode for InstructionTXA {
@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const TXS: u8 = 0x9A;
 
 /// Copies the current contents of the X register into the stack register.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionTXS;
 
 impl OpCode for InstructionTXS {
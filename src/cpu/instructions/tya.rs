@@ -3,7 +3,7 @@ use crate::{Instruction, OpCode, CPU};
 pub const TYA: u8 = 0x98;
 
 /// Copies the current contents of the Y register into the accumulator and sets the zero and negative flags as appropriate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionTYA;
 
 impl OpCode for InstructionTYA {
@@ -5,7 +5,7 @@ use super::{InstructionAND, InstructionTXA};
 pub const XAA_IMMEDIATE: u8 = 0x8B;
 
 // Unknown operation, higly unstable
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionXAA {
     txa: InstructionTXA,
     and: InstructionAND,
@@ -5,7 +5,7 @@ pub const XAS_ABSOLUTEY: u8 = 0x9B;
 /// A logical AND is performed, bit by bit, on the accumulator contents using the contents of the X register,
 /// storing the result in the stack pointer. Then AND the stack pointer with the high byte of the target address + 1,
 /// storing the result in memory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionXAS {
     addr: u16,
 }
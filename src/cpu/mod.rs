@@ -1,7 +1,10 @@
 pub use instructions::*;
 
+use std::{io, path::Path};
+
+use crate::ppu::registers::OAMDATA;
 use crate::trace::Trace;
-use crate::{AddressingMode, Bus, Interrupt, Mem, OpCode, Rom};
+use crate::{AddressingMode, Bus, Interrupt, InterruptType, MapperError, Mem, OpCode, Rom};
 use crate::{PROGRAM_START, STACK, STACK_SIZE};
 
 pub mod instructions;
@@ -32,6 +35,29 @@ bitflags::bitflags! {
     }
 }
 
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunOptions {
+    /// Return from `run` after a BRK instruction has been executed,
+    /// handy to end small programs without a JAM
+    pub stop_on_brk: bool,
+}
+
+/// What happened during a single `CPU::step`
+#[derive(Debug, Default, Clone)]
+pub struct StepResult {
+    /// The instruction executed, `None` when the step entered an interrupt handler
+    /// or the CPU is jammed
+    pub instruction: Option<Instruction>,
+    /// CPU cycles consumed
+    pub cycles: usize,
+    /// The vector taken by an interrupt sequence (NMI or IRQ entry, BRK, or an NMI hijacking them)
+    pub interrupt: Option<InterruptType>,
+    /// The PPU finished a frame during this step
+    pub frame_complete: bool,
+    /// The CPU is halted by a JAM instruction, only a reset brings it back
+    pub jammed: bool,
+}
+
 #[derive(Debug)]
 pub struct CPU {
     pub(crate) register_a: u8,
@@ -41,13 +67,35 @@ pub struct CPU {
     pub program_counter: u16,
     pub(crate) stack_pointer: u8,
     pub(crate) bus: Bus,
+    pub run_options: RunOptions,
+    /// Instruction register, the opcode of the instruction being executed
+    pub(crate) opcode: u8,
+    /// Interrupt lines are sampled at the end of every cycle,
+    /// but an instruction only sees them as they were before its last cycle
+    sampled_interrupt: Option<InterruptType>,
+    polled_interrupt: Option<InterruptType>,
+    serviced_interrupt: Option<InterruptType>,
+    frame_complete: bool,
+    /// Cycles of the current step spent halted by DMA
+    dma_cycles: usize,
+    pub(crate) jammed: bool,
+    #[cfg(test)]
+    pub(crate) bus_activity: Vec<BusCycle>,
+}
+
+/// A single CPU bus cycle, recorded by tests
+#[cfg(test)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BusCycle {
+    Read(u16, u8),
+    Write(u16, u8),
 }
 
 impl CPU {
-    fn new_inner(rom: Rom) -> Self {
-        let mut bus = Bus::new(rom);
+    fn new_inner(rom: Rom) -> Result<Self, MapperError> {
+        let mut bus = Bus::new(rom)?;
 
-        Self {
+        Ok(Self {
             register_a: 0,
             register_x: 0,
             register_y: 0,
@@ -55,10 +103,20 @@ impl CPU {
             program_counter: bus.mem_read_u16(PROGRAM_START),
             stack_pointer: STACK_SIZE - 2,
             bus,
-        }
+            run_options: RunOptions::default(),
+            opcode: 0,
+            sampled_interrupt: None,
+            polled_interrupt: None,
+            serviced_interrupt: None,
+            frame_complete: false,
+            dma_cycles: 0,
+            jammed: false,
+            #[cfg(test)]
+            bus_activity: Vec::new(),
+        })
     }
 
-    pub fn new(rom: Rom) -> Self {
+    pub fn new(rom: Rom) -> Result<Self, MapperError> {
         Self::new_inner(rom)
     }
 
@@ -66,23 +124,57 @@ impl CPU {
     pub fn new_test(program: &[u8]) -> Self {
         use crate::tests::test_rom;
 
-        Self::new_inner(test_rom(program))
+        let mut cpu = Self::new_inner(test_rom(program)).unwrap();
+        cpu.run_options.stop_on_brk = true;
+        cpu
     }
 
-    fn swap_rom_inner(&mut self, rom: Rom) {
-        self.bus.insert_rom(rom);
+    fn swap_rom_inner(&mut self, rom: Rom) -> Result<(), MapperError> {
+        self.bus.insert_rom(rom)?;
         self.reset_program_counter();
+        Ok(())
+    }
+
+    pub fn swap_rom(&mut self, rom: Rom) -> Result<(), MapperError> {
+        self.swap_rom_inner(rom)
     }
 
-    pub fn swap_rom(&mut self, rom: Rom) {
-        self.swap_rom_inner(rom);
+    /// Keep the battery RAM of the cartridge in the `.sav` file next to `rom_path`
+    pub fn load_save_file(&mut self, rom_path: impl AsRef<Path>) -> io::Result<()> {
+        self.bus.load_save_file(rom_path)
+    }
+
+    /// Write the battery RAM to its save file, it is also written when the CPU is dropped
+    pub fn flush_save_file(&self) -> io::Result<()> {
+        self.bus.flush_save_file()
+    }
+
+    /// Resample the audio to `sample_rate` Hz, 44.1 kHz by default
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.bus.apu.set_sample_rate(sample_rate);
+    }
+
+    /// Audio produced since the last call, to pull once `StepResult::frame_complete` is set
+    pub fn audio_samples(&mut self) -> Vec<f32> {
+        self.bus.apu.samples()
+    }
+
+    /// Audio produced since the last call as signed 16-bit PCM
+    pub fn audio_samples_i16(&mut self) -> Vec<i16> {
+        self.bus.apu.samples_i16()
     }
 
     #[cfg(test)]
     pub fn swap_test_rom(&mut self, program: &[u8]) {
         use crate::rom::tests::test_rom;
 
-        self.swap_rom_inner(test_rom(program));
+        self.swap_rom_inner(test_rom(program)).unwrap();
+    }
+
+    /// Read the top of the stack without pulling it,
+    /// the cycle pull instructions spend incrementing the stack pointer
+    pub(crate) fn stack_dummy_read(&mut self) {
+        self.mem_read(STACK + self.stack_pointer as u16);
     }
 
     pub fn stack_pull(&mut self) -> u8 {
@@ -118,7 +210,7 @@ impl CPU {
     }
 
     pub fn reset_program_counter(&mut self) {
-        self.program_counter = self.mem_read_u16(PROGRAM_START);
+        self.program_counter = self.bus.mem_read_u16(PROGRAM_START);
     }
 
     pub fn reset_stack_pointer(&mut self) {
@@ -130,41 +222,175 @@ impl CPU {
         self.reset_status();
         self.reset_program_counter();
         self.reset_stack_pointer();
+        self.jammed = false;
     }
 
     pub fn run(&mut self) {
         self.run_with_callback(|_| {});
     }
 
+    /// Run until a JAM, or a BRK when `RunOptions::stop_on_brk` is set.
+    /// `callback` is called before every instruction, after any interrupt entry.
     pub fn run_with_callback<F>(&mut self, mut callback: F)
     where
         F: FnMut(&mut Self),
     {
         loop {
-            if self.bus.poll_nmi_interrupt().is_some() {
-                self.interrupt(Interrupt::NMI);
+            if self.pending_interrupt().is_none() {
+                callback(self);
             }
 
-            callback(self);
+            let step = self.step();
+
+            let stop = match step.instruction {
+                Some(Instruction::BRK(_)) => self.run_options.stop_on_brk,
+                _ => step.jammed,
+            };
+            if stop {
+                return;
+            }
+        }
+    }
 
+    /// Step until `predicate` returns true, returning the step it accepted
+    pub fn run_until<F>(&mut self, mut predicate: F) -> StepResult
+    where
+        F: FnMut(&mut Self, &StepResult) -> bool,
+    {
+        loop {
+            let step = self.step();
+            if predicate(self, &step) {
+                return step;
+            }
+        }
+    }
+
+    /// Execute a single instruction, or enter a pending interrupt handler.
+    ///
+    /// A jammed CPU doesn't execute anything, but the rest of the console keeps running:
+    /// every step advances the bus by one cycle.
+    pub fn step(&mut self) -> StepResult {
+        let cycles = self.bus.cycles;
+        let mut step = StepResult::default();
+        self.frame_complete = false;
+        self.dma_cycles = 0;
+
+        if self.jammed {
+            self.cycle(|_| ());
+        } else if let Some(interrupt) = self.pending_interrupt() {
+            if interrupt.ty == InterruptType::NMI {
+                self.bus.poll_nmi_interrupt();
+            }
+
+            // The opcode fetch is discarded and the program counter isn't incremented
+            self.mem_read(self.program_counter);
+            self.mem_read(self.program_counter);
+            self.interrupt(interrupt);
+        } else {
             let instruction = Instruction::fetch(self);
+            let addressing_mode = self.get_addressing_mode();
+
+            // Single byte instructions still read the byte following the opcode
+            if matches!(
+                addressing_mode,
+                AddressingMode::Implied | AddressingMode::Accumulator
+            ) {
+                self.mem_read(self.program_counter.wrapping_add(1));
+            }
 
-            let stop = matches!(instruction, Instruction::BRK(_) | Instruction::JAM(_));
+            self.program_counter = self.program_counter.wrapping_add(addressing_mode.bytes());
 
-            self.program_counter = self
-                .program_counter
-                .wrapping_add(self.get_addressing_mode().bytes());
+            instruction.clone().execute(self);
 
-            let cycles = instruction.cycles();
+            debug_assert_eq!(
+                self.bus.cycles - cycles - self.dma_cycles,
+                instruction.cycles() as usize,
+                "{instruction:?}"
+            );
 
-            instruction.execute(self);
+            step.instruction = Some(instruction);
+        }
 
-            self.bus.tick(cycles);
+        step.interrupt = self.serviced_interrupt.take();
+        step.frame_complete = self.frame_complete;
+        step.jammed = self.jammed;
+        step.cycles = self.bus.cycles - cycles;
+        step
+    }
 
-            if stop {
-                return;
-            }
+    /// The interrupt the CPU would enter before its next instruction
+    fn pending_interrupt(&self) -> Option<Interrupt> {
+        match self.polled_interrupt? {
+            InterruptType::NMI => Some(Interrupt::NMI),
+            InterruptType::IRQ => Some(Interrupt::IRQ),
+            InterruptType::BRK => unreachable!("BRK is an instruction"),
+        }
+    }
+
+    /// Advance the console by one CPU cycle, performing a single bus access
+    fn cycle<T>(&mut self, access: impl FnOnce(&mut Bus) -> T) -> T {
+        self.frame_complete |= self.bus.tick(1);
+        let result = access(&mut self.bus);
+
+        self.polled_interrupt = self.sampled_interrupt;
+        self.sampled_interrupt = if self.bus.nmi_pending() {
+            Some(InterruptType::NMI)
+        } else if self.bus.irq() && !self.status.contains(Status::INTERRUPT_DISABLE) {
+            Some(InterruptType::IRQ)
+        } else {
+            None
+        };
+
+        result
+    }
+
+    /// The DMC can only halt the CPU on a read cycle, which is repeated on the halt and dummy
+    /// cycles and on an alignment cycle when the fetch would land on a put cycle.
+    /// Reads with side effects ($2007, $4016...) are performed that many extra times
+    fn dmc_dma(&mut self, addr: u16, sample_addr: u16) {
+        let cycles = self.bus.cycles;
+        self.cycle(|bus| bus.mem_read(addr));
+        self.cycle(|bus| bus.mem_read(addr));
+        if !self.bus.apu.put_cycle() {
+            self.cycle(|bus| bus.mem_read(addr));
         }
+        self.cycle(|bus| bus.dmc_fetch(sample_addr));
+        self.dma_cycles += self.bus.cycles - cycles;
+    }
+
+    /// A $4014 write halts the CPU on its next read cycle, then copies the 256 bytes of `page`
+    /// to OAM through $2004, reading on get cycles and writing on put cycles: 513 cycles,
+    /// or 514 with the alignment cycle when the halt lands on a get cycle
+    fn oam_dma(&mut self, addr: u16, page: u8) {
+        let cycles = self.bus.cycles;
+        self.cycle(|bus| bus.mem_read(addr));
+        if !self.bus.apu.put_cycle() {
+            self.cycle(|bus| bus.mem_read(addr));
+        }
+        for low in 0..=0xFF {
+            let data = self.cycle(|bus| bus.mem_read(u16::from_be_bytes([page, low])));
+            self.cycle(|bus| bus.mem_write(OAMDATA, data));
+        }
+        self.dma_cycles += self.bus.cycles - cycles;
+    }
+
+    /// Read the opcode at the program counter into the instruction register
+    pub(crate) fn fetch_opcode(&mut self) -> u8 {
+        self.opcode = self.mem_read(self.program_counter);
+        self.opcode
+    }
+
+    /// Read-modify-write instructions write the unmodified value back
+    /// on the cycle the new one is computed, then write the result
+    pub(crate) fn read_modify_write<F>(&mut self, addr: u16, modify: F) -> u8
+    where
+        F: FnOnce(&mut Self, u8) -> u8,
+    {
+        let value = self.mem_read(addr);
+        self.mem_write(addr, value);
+        let result = modify(self, value);
+        self.mem_write(addr, result);
+        result
     }
 
     fn update_zero_and_negative_flags(&mut self, result: u8) {
@@ -182,10 +408,12 @@ impl CPU {
     }
 
     pub fn get_addressing_mode(&mut self) -> AddressingMode {
-        AddressingMode::new(self.mem_read(self.program_counter))
+        AddressingMode::new(self.opcode)
     }
 
     /// (address, page_crossed)
+    ///
+    /// Reads the operand of the current instruction, with the dummy reads of its addressing mode
     pub fn get_operand_address(&mut self) -> (u16, bool) {
         use AddressingMode as AM;
 
@@ -202,24 +430,30 @@ impl CPU {
         match mode {
             AM::Immediate => (program_counter, false),
             AM::ZeroPage => (self.mem_read(program_counter) as u16, false),
-            AM::ZeroPageX => (
-                self.mem_read(program_counter).wrapping_add(self.register_x) as u16,
-                false,
-            ),
-            AM::ZeroPageY => (
-                self.mem_read(program_counter).wrapping_add(self.register_y) as u16,
-                false,
-            ),
+            AM::ZeroPageX | AM::ZeroPageY => {
+                let base = self.mem_read(program_counter);
+                // The base address is read while the index is added
+                self.mem_read(base as u16);
+                let index = match mode {
+                    AM::ZeroPageX => self.register_x,
+                    _ => self.register_y,
+                };
+                (base.wrapping_add(index) as u16, false)
+            }
             AM::Absolute => (self.mem_read_u16(program_counter), false),
             AM::AbsoluteX => {
                 let base = self.mem_read_u16(program_counter);
                 let addr = base.wrapping_add(self.register_x as u16);
-                (addr, page_cross(base, addr))
+                let page_crossed = page_cross(base, addr);
+                self.indexed_dummy_read(base, addr, page_crossed);
+                (addr, page_crossed)
             }
             AM::AbsoluteY => {
                 let base = self.mem_read_u16(program_counter);
                 let addr = base.wrapping_add(self.register_y as u16);
-                (addr, page_cross(base, addr))
+                let page_crossed = page_cross(base, addr);
+                self.indexed_dummy_read(base, addr, page_crossed);
+                (addr, page_crossed)
             }
             AM::Indirect => {
                 let base = self.mem_read_u16(program_counter);
@@ -246,7 +480,10 @@ impl CPU {
                 (addr, false)
             }
             AM::IndirectX => {
-                let pos = self.mem_read(program_counter).wrapping_add(self.register_x);
+                let base = self.mem_read(program_counter);
+                // The pointer is read while X is added
+                self.mem_read(base as u16);
+                let pos = base.wrapping_add(self.register_x);
                 let lo = self.mem_read(pos as u16);
                 let hi = self.mem_read(pos.wrapping_add(1) as u16);
                 (u16::from_le_bytes([lo, hi]), false)
@@ -257,7 +494,9 @@ impl CPU {
                 let hi = self.mem_read(base.wrapping_add(1) as u16);
                 let deref_base = u16::from_le_bytes([lo, hi]);
                 let deref = deref_base.wrapping_add(self.register_y as u16);
-                (deref, page_cross(deref, deref_base))
+                let page_crossed = page_cross(deref, deref_base);
+                self.indexed_dummy_read(deref_base, deref, page_crossed);
+                (deref, page_crossed)
             }
             AM::Relative => {
                 let skip = self.mem_read(program_counter) as i8;
@@ -269,8 +508,28 @@ impl CPU {
         }
     }
 
+    /// Indexing adds to the low byte of the address first, and the bus is read
+    /// before the carry reaches the high byte: always for writes, only on page crossings for reads
+    fn indexed_dummy_read(&mut self, base: u16, addr: u16, page_crossed: bool) {
+        if page_crossed || is_write_opcode(self.opcode) {
+            self.mem_read((base & 0xFF00) | (addr & 0x00FF));
+        }
+    }
+
     pub fn branch(&mut self, target: u16, condition: bool) {
         if condition {
+            // The next opcode is read while the offset is added,
+            // then the target before its high byte is fixed when a page is crossed
+            let polled_interrupt = self.polled_interrupt;
+            self.mem_read(self.program_counter);
+
+            if target & 0xFF00 == self.program_counter & 0xFF00 {
+                // Interrupts aren't polled again on the extra cycle of a branch within the page
+                self.polled_interrupt = polled_interrupt;
+            } else {
+                self.mem_read((self.program_counter & 0xFF00) | (target & 0x00FF));
+            }
+
             self.program_counter = target;
         }
     }
@@ -299,33 +558,45 @@ impl CPU {
         self.update_zero_and_negative_flags(self.register_a);
     }
 
-    fn interrupt(&mut self, interrupt: Interrupt) {
+    /// Push the return address and status, then jump through the interrupt vector.
+    /// Takes the five cycles following the two reads of the opcode (or of the BRK signature byte).
+    pub(crate) fn interrupt(&mut self, interrupt: Interrupt) {
         self.stack_push_u16(self.program_counter);
 
-        let mut flag = self.status;
-        flag.set(Status::BREAK_COMMAND, false);
-        self.stack_push(flag.bits());
+        let flag = (self.status.bits() & !(Status::BREAK_COMMAND | Status::UNUSED).bits())
+            | interrupt.b_flag_mask;
+        self.stack_push(flag);
 
         self.status.insert(Status::INTERRUPT_DISABLE);
 
-        self.bus.tick(interrupt.cpu_cycles);
+        // An NMI asserted while a BRK or IRQ is pushing to the stack hijacks its vector fetch,
+        // the pushed status keeps the B flag of the original interrupt
+        let interrupt = match interrupt.ty {
+            InterruptType::IRQ | InterruptType::BRK if self.bus.poll_nmi_interrupt().is_some() => {
+                Interrupt::NMI
+            }
+            _ => interrupt,
+        };
 
+        self.serviced_interrupt = Some(interrupt.ty);
         self.program_counter = self.mem_read_u16(interrupt.handler_addr);
     }
 
-    pub fn trace(&mut self) -> Trace {
+    /// Describe the instruction at the program counter, without any side effect
+    pub fn trace(&self) -> Trace {
         use crate::trace::*;
 
-        let addressing_mode = self.get_addressing_mode();
+        let code = self.peek(self.program_counter);
+        let addressing_mode = AddressingMode::new(code);
 
         Trace {
             program_counter: self.program_counter,
             opcode: OpCodeTrace {
-                code: self.mem_read(self.program_counter),
-                address: self.mem_read_u16(self.program_counter + 1),
+                code,
+                address: self.peek_u16(self.program_counter + 1),
                 len: addressing_mode.bytes(),
             },
-            name: Instruction::name(self.mem_read(self.program_counter)),
+            name: Instruction::name(code),
             asm: InstructionTrace::new(self),
             registers: RegistersTrace {
                 register_a: self.register_a,
@@ -343,12 +614,249 @@ impl CPU {
     }
 }
 
+/// Every access goes through the bus on a cycle of its own
 impl Mem for CPU {
     fn mem_read(&mut self, addr: u16) -> u8 {
-        self.bus.mem_read(addr)
+        if let Some(page) = self.bus.take_oam_dma() {
+            self.oam_dma(addr, page);
+        }
+        if let Some(sample_addr) = self.bus.apu.dmc.dma_address() {
+            self.dmc_dma(addr, sample_addr);
+        }
+
+        let data = self.cycle(|bus| bus.mem_read(addr));
+        #[cfg(test)]
+        self.bus_activity.push(BusCycle::Read(addr, data));
+        data
     }
 
     fn mem_write(&mut self, addr: u16, data: u8) {
-        self.bus.mem_write(addr, data);
+        self.cycle(|bus| bus.mem_write(addr, data));
+        #[cfg(test)]
+        self.bus_activity.push(BusCycle::Write(addr, data));
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        self.bus.peek(addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::ppu::registers::OAMADDR;
+    use crate::{IrqSource, APU_STATUS, DMC, PROGRAM};
+
+    use super::BusCycle::{Read, Write};
+    use super::*;
+
+    #[test_case(&[LDA_ABSOLUTEX, 0x00, 0x02], &[(0x0201, 0x42)], &[
+        Read(0x8000, LDA_ABSOLUTEX), Read(0x8001, 0x00), Read(0x8002, 0x02), Read(0x0201, 0x42),
+    ] ; "lda_absolute_x")]
+    #[test_case(&[LDA_ABSOLUTEX, 0xFF, 0x01], &[(0x0200, 0x42)], &[
+        Read(0x8000, LDA_ABSOLUTEX), Read(0x8001, 0xFF), Read(0x8002, 0x01), Read(0x0100, 0x00),
+        Read(0x0200, 0x42),
+    ] ; "lda_absolute_x_page_cross")]
+    #[test_case(&[STA_ABSOLUTEX, 0x00, 0x02], &[], &[
+        Read(0x8000, STA_ABSOLUTEX), Read(0x8001, 0x00), Read(0x8002, 0x02), Read(0x0201, 0x00),
+        Write(0x0201, 0x55),
+    ] ; "sta_absolute_x")]
+    #[test_case(&[STA_ABSOLUTEX, 0x00, 0x20], &[], &[
+        Read(0x8000, STA_ABSOLUTEX), Read(0x8001, 0x00), Read(0x8002, 0x20), Read(0x2001, 0x20),
+        Write(0x2001, 0x55),
+    ] ; "sta_absolute_x_ppu_register")]
+    #[test_case(&[STA_ABSOLUTEX, 0x00, 0x40], &[], &[
+        Read(0x8000, STA_ABSOLUTEX), Read(0x8001, 0x00), Read(0x8002, 0x40), Read(0x4001, 0x40),
+        Write(0x4001, 0x55),
+    ] ; "sta_absolute_x_apu_register")]
+    #[test_case(&[LDA_ZEROPAGEX, 0xFF], &[(0x0000, 0x42), (0x00FF, 0x11)], &[
+        Read(0x8000, LDA_ZEROPAGEX), Read(0x8001, 0xFF), Read(0x00FF, 0x11), Read(0x0000, 0x42),
+    ] ; "lda_zero_page_x")]
+    #[test_case(&[LDA_INDIRECTY, 0x10], &[(0x0010, 0xFF), (0x0011, 0x01), (0x0200, 0x42)], &[
+        Read(0x8000, LDA_INDIRECTY), Read(0x8001, 0x10), Read(0x0010, 0xFF), Read(0x0011, 0x01),
+        Read(0x0100, 0x00), Read(0x0200, 0x42),
+    ] ; "lda_indirect_y_page_cross")]
+    #[test_case(&[STA_INDIRECTX, 0x10], &[(0x0011, 0x00), (0x0012, 0x03)], &[
+        Read(0x8000, STA_INDIRECTX), Read(0x8001, 0x10), Read(0x0010, 0x00), Read(0x0011, 0x00),
+        Read(0x0012, 0x03), Write(0x0300, 0x55),
+    ] ; "sta_indirect_x")]
+    #[test_case(&[INC_ZEROPAGE, 0x10], &[(0x0010, 0x07)], &[
+        Read(0x8000, INC_ZEROPAGE), Read(0x8001, 0x10), Read(0x0010, 0x07), Write(0x0010, 0x07),
+        Write(0x0010, 0x08),
+    ] ; "inc_zero_page")]
+    #[test_case(&[ASL_ABSOLUTEX, 0x00, 0x02], &[(0x0201, 0x41)], &[
+        Read(0x8000, ASL_ABSOLUTEX), Read(0x8001, 0x00), Read(0x8002, 0x02), Read(0x0201, 0x41),
+        Read(0x0201, 0x41), Write(0x0201, 0x41), Write(0x0201, 0x82),
+    ] ; "asl_absolute_x")]
+    #[test_case(&[PHA, NOP], &[], &[
+        Read(0x8000, PHA), Read(0x8001, NOP), Write(0x01FD, 0x55),
+    ] ; "pha")]
+    #[test_case(&[PLA, NOP], &[(0x01FE, 0x42)], &[
+        Read(0x8000, PLA), Read(0x8001, NOP), Read(0x01FD, 0x00), Read(0x01FE, 0x42),
+    ] ; "pla")]
+    #[test_case(&[JSR, 0x34, 0x12], &[], &[
+        Read(0x8000, JSR), Read(0x8001, 0x34), Read(0x01FD, 0x00), Write(0x01FD, 0x80),
+        Write(0x01FC, 0x02), Read(0x8002, 0x12),
+    ] ; "jsr")]
+    #[test_case(&[RTS, NOP], &[(0x01FE, 0x0F), (0x01FF, 0x80)], &[
+        Read(0x8000, RTS), Read(0x8001, NOP), Read(0x01FD, 0x00), Read(0x01FE, 0x0F),
+        Read(0x01FF, 0x80), Read(0x800F, 0x00),
+    ] ; "rts")]
+    #[test_case(&[BEQ, 0x10], &[], &[
+        Read(0x8000, BEQ), Read(0x8001, 0x10),
+    ] ; "branch_not_taken")]
+    #[test_case(&[BNE, 0xFD], &[], &[
+        Read(0x8000, BNE), Read(0x8001, 0xFD), Read(0x8002, 0x00), Read(0x80FF, 0x00),
+    ] ; "branch_page_cross")]
+    #[test_case(&[JMP_INDIRECT, 0xFF, 0x02], &[(0x02FF, 0x34), (0x0200, 0x12)], &[
+        Read(0x8000, JMP_INDIRECT), Read(0x8001, 0xFF), Read(0x8002, 0x02), Read(0x02FF, 0x34),
+        Read(0x0200, 0x12),
+    ] ; "jmp_indirect_page_wrap")]
+    fn bus_activity(program: &[u8], ram: &[(u16, u8)], expected: &[BusCycle]) {
+        let mut cpu = CPU::new_test(program);
+        cpu.register_a = 0x55;
+        cpu.register_x = 1;
+        cpu.register_y = 1;
+        for &(addr, data) in ram {
+            cpu.mem_write(addr, data);
+        }
+        cpu.bus_activity.clear();
+
+        let step = cpu.step();
+        assert_eq!(cpu.bus_activity, expected);
+        assert_eq!(step.cycles, expected.len());
+    }
+
+    #[test]
+    fn step() {
+        let mut cpu = CPU::new_test(&[LDA_IMMEDIATE, 0x05, NOP]);
+
+        let step = cpu.step();
+        assert!(matches!(step.instruction, Some(Instruction::LDA(_))));
+        assert_eq!(step.cycles, 2);
+        assert_eq!(step.interrupt, None);
+        assert!(!step.jammed);
+        assert_eq!(cpu.register_a, 0x05);
+        assert_eq!(cpu.program_counter, PROGRAM + 2);
+    }
+
+    #[test]
+    fn step_interrupt() {
+        let mut cpu = CPU::new_test(&[CLI, NOP, NOP]);
+        cpu.bus.set_irq(IrqSource::EXTERNAL, true);
+
+        // The IRQ is only taken after the instruction following CLI
+        assert!(matches!(cpu.step().instruction, Some(Instruction::CLI(_))));
+        assert!(matches!(cpu.step().instruction, Some(Instruction::NOP(_))));
+
+        cpu.bus_activity.clear();
+        let step = cpu.step();
+        assert!(step.instruction.is_none());
+        assert_eq!(step.interrupt, Some(InterruptType::IRQ));
+        assert_eq!(step.cycles, 7);
+        assert_eq!(
+            cpu.bus_activity,
+            [
+                Read(PROGRAM + 2, NOP),
+                Read(PROGRAM + 2, NOP),
+                Write(0x01FD, 0x80),
+                Write(0x01FC, 0x02),
+                Write(0x01FB, Status::UNUSED.bits()),
+                Read(0xFFFE, 0x00),
+                Read(0xFFFF, 0x00),
+            ]
+        );
+    }
+
+    #[test_case(false, 513 ; "aligned")]
+    #[test_case(true, 514 ; "alignment_cycle")]
+    fn oam_dma(odd_cycle: bool, stall: usize) {
+        let mut cpu = CPU::new_test(&[LDA_IMMEDIATE, 0x02, STA_ABSOLUTE, 0x14, 0x40, NOP]);
+        for low in 0..=0xFF {
+            cpu.mem_write(0x0200 + low, low as u8);
+        }
+        cpu.mem_write(OAMADDR, 0x10);
+        if odd_cycle {
+            cpu.bus.tick(1);
+        }
+
+        cpu.step();
+        cpu.step();
+        // The DMA halts the opcode fetch of the next instruction
+        let step = cpu.step();
+        assert_eq!(step.cycles, 2 + stall);
+        assert_eq!(cpu.bus.ppu.oam_data[0x10], 0x00);
+        assert_eq!(cpu.bus.ppu.oam_data[0x0F], 0xFF);
+        assert_eq!(*cpu.bus.ppu.oam_addr, 0x10);
+    }
+
+    #[test_case(false, 3 ; "aligned")]
+    #[test_case(true, 4 ; "alignment_cycle")]
+    fn dmc_dma(odd_cycle: bool, stall: usize) {
+        let mut cpu = CPU::new_test(&[LDA_ABSOLUTE, 0x00, 0x02]);
+        if odd_cycle {
+            cpu.bus.tick(1);
+        }
+        cpu.bus.mem_write(DMC, 0b1000_0000);
+        cpu.bus.mem_write(APU_STATUS, 0b0001_0000);
+        cpu.bus_activity.clear();
+
+        // The opcode fetch is halted, then repeated once the sample is fetched
+        let step = cpu.step();
+        assert_eq!(step.cycles, 4 + stall);
+        assert_eq!(cpu.bus_activity.len(), 4);
+        assert!(cpu.bus.irq_sources().contains(IrqSource::DMC));
+        assert!(!cpu.bus.apu.dmc.is_active());
+    }
+
+    #[test]
+    fn nmi_enabled_during_vblank_is_delayed() {
+        let mut cpu = CPU::new_test(&[LDA_IMMEDIATE, 0x80, STA_ABSOLUTE, 0x00, 0x20, NOP, NOP]);
+        cpu.bus.ppu.status.set_vblank_status(true);
+
+        assert!(matches!(cpu.step().instruction, Some(Instruction::LDA(_))));
+        assert!(matches!(cpu.step().instruction, Some(Instruction::STA(_))));
+        // The NMI edge happens on the last cycle of STA, too late to be polled
+        assert!(matches!(cpu.step().instruction, Some(Instruction::NOP(_))));
+        assert_eq!(cpu.step().interrupt, Some(InterruptType::NMI));
+    }
+
+    #[test]
+    fn step_jammed() {
+        let mut cpu = CPU::new_test(&[KIL_IMPLIED1, NOP]);
+
+        let step = cpu.step();
+        assert!(matches!(step.instruction, Some(Instruction::JAM(_))));
+        assert!(step.jammed);
+
+        let step = cpu.step();
+        assert!(step.instruction.is_none());
+        assert!(step.jammed);
+        assert_eq!(step.cycles, 1);
+        assert_eq!(cpu.program_counter, PROGRAM + 1);
+    }
+
+    #[test]
+    fn trace_does_not_read() {
+        let mut cpu = CPU::new_test(&[LDA_ABSOLUTE, 0x02, 0x20]);
+        cpu.bus.ppu.status.set_vblank_status(true);
+
+        assert!(cpu.trace().to_string().contains("LDA $2002 = 80"));
+        assert!(cpu.bus.ppu.status.is_in_vblank());
+
+        cpu.step();
+        assert_eq!(cpu.register_a, 0x80);
+        assert!(!cpu.bus.ppu.status.is_in_vblank());
+    }
+
+    #[test]
+    fn run_until_frame_complete() {
+        let [lo, hi] = PROGRAM.to_le_bytes();
+        let mut cpu = CPU::new_test(&[JMP_ABSOLUTE, lo, hi]);
+
+        let step = cpu.run_until(|_, step| step.frame_complete);
+        assert!(matches!(step.instruction, Some(Instruction::JMP(_))));
+        assert_eq!(cpu.bus.ppu.scanline, 0);
     }
 }
@@ -1,3 +1,15 @@
+bitflags::bitflags! {
+    /// Devices sharing the CPU /IRQ line.
+    /// The line is wired-OR: it stays asserted while any of them is pulling it low.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct IrqSource: u8 {
+        const FRAME_COUNTER = 0b0001;
+        const DMC           = 0b0010;
+        const MAPPER        = 0b0100;
+        const EXTERNAL      = 0b1000;
+    }
+}
+
 pub struct Interrupt {
     pub ty: InterruptType,
     pub handler_addr: u16,
@@ -10,10 +22,27 @@ impl Interrupt {
         ty: InterruptType::NMI,
         handler_addr: 0xFFFA,
         b_flag_mask: 0b0010_0000,
-        cpu_cycles: 2,
+        cpu_cycles: 7,
+    };
+
+    pub const IRQ: Self = Self {
+        ty: InterruptType::IRQ,
+        handler_addr: 0xFFFE,
+        b_flag_mask: 0b0010_0000,
+        cpu_cycles: 7,
+    };
+
+    pub const BRK: Self = Self {
+        ty: InterruptType::BRK,
+        handler_addr: 0xFFFE,
+        b_flag_mask: 0b0011_0000,
+        cpu_cycles: 7,
     };
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InterruptType {
     NMI,
+    IRQ,
+    BRK,
 }
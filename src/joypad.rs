@@ -0,0 +1,172 @@
+use core::fmt::Debug;
+
+pub const JOYPAD1: u16 = 0x4016;
+pub const JOYPAD2: u16 = 0x4017;
+
+bitflags::bitflags! {
+    /// Buttons of the standard controller, in the order they are shifted out
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct JoypadButton: u8 {
+        const A      = 0b0000_0001;
+        const B      = 0b0000_0010;
+        const SELECT = 0b0000_0100;
+        const START  = 0b0000_1000;
+        const UP     = 0b0001_0000;
+        const DOWN   = 0b0010_0000;
+        const LEFT   = 0b0100_0000;
+        const RIGHT  = 0b1000_0000;
+    }
+}
+
+/// The two controller ports on the front of the console
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerPort {
+    /// Read through $4016
+    One,
+    /// Read through $4017
+    Two,
+}
+
+/// Peripheral plugged into a controller port.
+///
+/// Writes to $4016 drive the OUT lines of both ports, reads of $4016/$4017 clock the port
+/// and return its data lines (D0-D4); the upper bits are open bus and filled in by the bus.
+pub trait InputDevice: Debug {
+    /// Write to $4016, bit 0 is the strobe
+    fn write(&mut self, data: u8);
+
+    /// Read from the port's register
+    fn read(&mut self) -> u8;
+
+    /// The value `read` would return, without any side effect
+    fn peek(&self) -> u8;
+
+    /// Update the buttons being held, ignored by devices without any
+    fn set_buttons(&mut self, _buttons: JoypadButton) {}
+}
+
+/// Standard controller.
+///
+/// While the strobe is high the shift register is continuously reloaded with the buttons,
+/// so reads keep returning the state of A. Once it goes low, every read shifts out the next
+/// button and official controllers return 1 after the eighth one.
+#[derive(Debug, Default)]
+pub struct Joypad {
+    strobe: bool,
+    shift_register: u8,
+    buttons: JoypadButton,
+}
+
+impl Joypad {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn buttons(&self) -> JoypadButton {
+        self.buttons
+    }
+}
+
+impl InputDevice for Joypad {
+    fn write(&mut self, data: u8) {
+        self.strobe = data & 1 != 0;
+        if self.strobe {
+            self.shift_register = self.buttons.bits();
+        }
+    }
+
+    fn read(&mut self) -> u8 {
+        let data = self.peek();
+        if !self.strobe {
+            self.shift_register = (self.shift_register >> 1) | 0b1000_0000;
+        }
+        data
+    }
+
+    fn peek(&self) -> u8 {
+        if self.strobe {
+            self.buttons.bits() & 1
+        } else {
+            self.shift_register & 1
+        }
+    }
+
+    fn set_buttons(&mut self, buttons: JoypadButton) {
+        self.buttons = buttons;
+        if self.strobe {
+            self.shift_register = buttons.bits();
+        }
+    }
+}
+
+/// Nothing plugged in, the data lines read as 0
+#[derive(Debug, Default)]
+pub struct Unplugged;
+
+impl InputDevice for Unplugged {
+    fn write(&mut self, _data: u8) {}
+
+    fn read(&mut self) -> u8 {
+        0
+    }
+
+    fn peek(&self) -> u8 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(joypad: &mut Joypad) -> Vec<u8> {
+        (0..10).map(|_| joypad.read()).collect()
+    }
+
+    #[test]
+    fn shifts_out_buttons() {
+        let mut joypad = Joypad::new();
+        joypad.set_buttons(JoypadButton::A | JoypadButton::START | JoypadButton::RIGHT);
+
+        joypad.write(1);
+        joypad.write(0);
+        assert_eq!(read_all(&mut joypad), [1, 0, 0, 1, 0, 0, 0, 1, 1, 1]);
+    }
+
+    #[test]
+    fn strobe_high_returns_a() {
+        let mut joypad = Joypad::new();
+        joypad.set_buttons(JoypadButton::A);
+        joypad.write(1);
+
+        assert_eq!(read_all(&mut joypad), [1; 10]);
+
+        joypad.set_buttons(JoypadButton::B);
+        assert_eq!(joypad.read(), 0);
+    }
+
+    #[test]
+    fn latches_on_strobe() {
+        let mut joypad = Joypad::new();
+        joypad.set_buttons(JoypadButton::B);
+        joypad.write(1);
+        joypad.write(0);
+
+        joypad.set_buttons(JoypadButton::A);
+        assert_eq!(joypad.read(), 0);
+        assert_eq!(joypad.read(), 1);
+    }
+
+    #[test]
+    fn peek() {
+        let mut joypad = Joypad::new();
+        joypad.set_buttons(JoypadButton::A);
+        joypad.write(1);
+        joypad.write(0);
+
+        assert_eq!(joypad.peek(), 1);
+        assert_eq!(joypad.peek(), 1);
+        assert_eq!(joypad.read(), 1);
+        assert_eq!(joypad.peek(), 0);
+    }
+}
@@ -1,16 +1,24 @@
+pub mod apu;
 pub mod bus;
 pub mod cpu;
 pub mod interrupt;
+pub mod joypad;
+pub mod mapper;
 pub mod mem;
 pub mod opcode;
 pub mod ppu;
 pub mod rom;
+pub mod save;
 pub mod trace;
 
+pub use apu::*;
 pub use bus::*;
 pub use cpu::*;
 pub use interrupt::*;
+pub use joypad::*;
+pub use mapper::*;
 pub use mem::*;
 pub use opcode::*;
 pub use ppu::*;
 pub use rom::*;
+pub use save::*;
@@ -0,0 +1,134 @@
+use crate::{Mirroring, Rom, PRG_RAM, PRG_RAM_END, PROGRAM};
+
+use super::{Chr, Mapper, PrgRam};
+
+pub const AXROM_MAPPER: u16 = 7;
+
+const PRG_BANK_SIZE: usize = 0x8000;
+
+/// Mapper 7 (ANROM, AMROM, AOROM).
+/// A 32 KB PRG ROM bank is switched at $8000-$FFFF and bit 4 selects the single-screen nametable.
+#[derive(Debug)]
+pub struct AxROM {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+    /// 7  bit  0
+    /// ---- ----
+    /// xxxM xPPP
+    ///    |  |||
+    ///    |  +++- Select 32 KB PRG ROM bank for CPU $8000-$FFFF
+    ///    +------ Select 1 KB VRAM page for all 4 nametables
+    bank: u8,
+    bus_conflicts: bool,
+}
+
+impl AxROM {
+    pub fn new(rom: Rom, bus_conflicts: bool) -> Self {
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: PrgRam::new(rom.prg_ram_size, rom.prg_nvram_size),
+            chr: Chr::new(rom.chr_rom, rom.chr_ram_size + rom.chr_nvram_size),
+            bank: 0,
+            bus_conflicts,
+        }
+    }
+}
+
+impl Mapper for AxROM {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PRG_RAM..=PRG_RAM_END => self.prg_ram.read(addr),
+            PROGRAM..=0xFFFF => {
+                let banks = (self.prg_rom.len() / PRG_BANK_SIZE).max(1);
+                let bank = (self.bank & 0b111) as usize % banks;
+                self.prg_rom
+                    [(bank * PRG_BANK_SIZE + (addr - PROGRAM) as usize) % self.prg_rom.len()]
+            }
+            _ => 0,
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, mut data: u8) {
+        if addr < PROGRAM {
+            if (PRG_RAM..=PRG_RAM_END).contains(&addr) {
+                self.prg_ram.write(addr, data);
+            }
+            return;
+        }
+
+        if self.bus_conflicts {
+            data &= self.cpu_peek(addr);
+        }
+
+        self.bank = data;
+    }
+
+    fn ppu_peek(&self, addr: u16) -> u8 {
+        self.chr[addr as usize]
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        self.chr.write(addr as usize, data);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        if self.bank & 0b1_0000 != 0 {
+            Mirroring::SingleScreenB
+        } else {
+            Mirroring::SingleScreenA
+        }
+    }
+
+    fn chr_ram(&self) -> &[u8] {
+        self.chr.ram()
+    }
+
+    fn chr_ram_mut(&mut self) -> &mut [u8] {
+        self.chr.ram_mut()
+    }
+
+    fn battery_ram(&self) -> &[u8] {
+        self.prg_ram.battery()
+    }
+
+    fn battery_ram_mut(&mut self) -> &mut [u8] {
+        self.prg_ram.battery_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mapper_rom;
+
+    use super::*;
+
+    #[test]
+    fn switch_bank() {
+        let mut mapper = AxROM::new(mapper_rom(AXROM_MAPPER, 8, 1), false);
+        assert_eq!(mapper.cpu_read(0x8000), 0);
+
+        mapper.cpu_write(0x8000, 2);
+        assert_eq!(mapper.cpu_read(0x8000), 8);
+        assert_eq!(mapper.cpu_read(0xFFFF), 11);
+    }
+
+    #[test]
+    fn single_screen() {
+        let mut mapper = AxROM::new(mapper_rom(AXROM_MAPPER, 8, 1), false);
+        assert_eq!(mapper.mirroring(), Mirroring::SingleScreenA);
+
+        mapper.cpu_write(0x8000, 0b1_0000);
+        assert_eq!(mapper.mirroring(), Mirroring::SingleScreenB);
+    }
+
+    #[test]
+    fn bus_conflicts() {
+        let mut mapper = AxROM::new(mapper_rom(AXROM_MAPPER, 8, 1), true);
+
+        // $8000 holds 0, so every write is masked out
+        mapper.cpu_write(0x8000, 0b1_0011);
+        assert_eq!(mapper.cpu_read(0x8000), 0);
+        assert_eq!(mapper.mirroring(), Mirroring::SingleScreenA);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{Mirroring, Rom, CHR_ROM_PAGE_SIZE, PRG_RAM, PRG_RAM_END, PRG_ROM_PAGE_SIZE, PROGRAM};
+
+use super::{cartridge_vram, Chr, Mapper, PrgRam, CARTRIDGE_VRAM_SIZE};
+
+pub const CNROM_MAPPER: u16 = 3;
+
+/// Mapper 3 (CNROM).
+/// PRG ROM is fixed like NROM, an 8 KB CHR ROM bank is switched at $0000-$1FFF.
+#[derive(Debug)]
+pub struct CNROM {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+    /// Four-screen nametable RAM at $2800-$2FFF
+    vram: Vec<u8>,
+    mirroring: Mirroring,
+    chr_bank: u8,
+    bus_conflicts: bool,
+}
+
+impl CNROM {
+    pub fn new(rom: Rom, bus_conflicts: bool) -> Self {
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: PrgRam::new(rom.prg_ram_size, rom.prg_nvram_size),
+            chr: Chr::new(rom.chr_rom, rom.chr_ram_size + rom.chr_nvram_size),
+            vram: cartridge_vram(rom.screen_mirroring),
+            mirroring: rom.screen_mirroring,
+            chr_bank: 0,
+            bus_conflicts,
+        }
+    }
+}
+
+impl CNROM {
+    fn chr_addr(&self, addr: u16) -> usize {
+        let banks = self.chr.len() / CHR_ROM_PAGE_SIZE;
+        let bank = self.chr_bank as usize % banks;
+        bank * CHR_ROM_PAGE_SIZE + addr as usize
+    }
+}
+
+impl Mapper for CNROM {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PRG_RAM..=PRG_RAM_END => self.prg_ram.read(addr),
+            PROGRAM..=0xFFFF => {
+                let mut addr = (addr - PROGRAM) as usize;
+                if self.prg_rom.len() == PRG_ROM_PAGE_SIZE {
+                    // mirror if needed
+                    addr %= PRG_ROM_PAGE_SIZE;
+                }
+                self.prg_rom[addr]
+            }
+            _ => 0,
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, mut data: u8) {
+        if addr < PROGRAM {
+            if (PRG_RAM..=PRG_RAM_END).contains(&addr) {
+                self.prg_ram.write(addr, data);
+            }
+            return;
+        }
+
+        if self.bus_conflicts {
+            data &= self.cpu_peek(addr);
+        }
+
+        self.chr_bank = data;
+    }
+
+    fn ppu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x2000.. => self.vram[addr as usize % CARTRIDGE_VRAM_SIZE],
+            _ => self.chr[self.chr_addr(addr)],
+        }
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x2000.. => self.vram[addr as usize % CARTRIDGE_VRAM_SIZE] = data,
+            _ => self.chr.write(self.chr_addr(addr), data),
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn chr_ram(&self) -> &[u8] {
+        self.chr.ram()
+    }
+
+    fn chr_ram_mut(&mut self) -> &mut [u8] {
+        self.chr.ram_mut()
+    }
+
+    fn battery_ram(&self) -> &[u8] {
+        self.prg_ram.battery()
+    }
+
+    fn battery_ram_mut(&mut self) -> &mut [u8] {
+        self.prg_ram.battery_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mapper_rom;
+
+    use super::*;
+
+    #[test]
+    fn switch_bank() {
+        let mut mapper = CNROM::new(mapper_rom(CNROM_MAPPER, 2, 4), false);
+        assert_eq!(mapper.ppu_read(0x0000), 0);
+
+        mapper.cpu_write(0x8000, 2);
+        assert_eq!(mapper.ppu_read(0x0000), 16);
+        assert_eq!(mapper.ppu_read(0x1FFF), 23);
+    }
+
+    #[test]
+    fn bus_conflicts() {
+        let mut mapper = CNROM::new(mapper_rom(CNROM_MAPPER, 2, 4), true);
+
+        // $A000 holds 1, so writing 3 selects bank 1
+        mapper.cpu_write(0xA000, 3);
+        assert_eq!(mapper.ppu_read(0x0000), 8);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{Mirroring, Rom, PRG_RAM, PRG_RAM_END, PRG_ROM_PAGE_SIZE, PROGRAM};
+
+use super::{Chr, Mapper, PrgRam};
+
+pub const MMC1_MAPPER: u16 = 1;
+
+const CHR_BANK_SIZE: usize = 0x1000;
+const SHIFT_REGISTER_RESET: u8 = 0b1_0000;
+
+/// Mapper 1 (SxROM).
+///
+/// Registers are loaded serially: every write to $8000-$FFFF shifts bit 0 into a 5-bit shift register,
+/// and the fifth write copies it into the register selected by bits 13-14 of the address.
+/// Writing a value with bit 7 set resets the shift register instead.
+#[derive(Debug)]
+pub struct MMC1 {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+    shift_register: u8,
+    /// 4bit0
+    /// -----
+    /// CPPMM
+    /// |||||
+    /// |||++- Mirroring (0: one-screen, lower bank; 1: one-screen, upper bank;
+    /// |||               2: vertical; 3: horizontal)
+    /// |++--- PRG ROM bank mode (0, 1: switch 32 KB at $8000, ignoring low bit of bank number;
+    /// |                         2: fix first bank at $8000 and switch 16 KB bank at $C000;
+    /// |                         3: fix last bank at $C000 and switch 16 KB bank at $8000)
+    /// +----- CHR ROM bank mode (0: switch 8 KB at a time; 1: switch two separate 4 KB banks)
+    control: u8,
+    chr_bank_0: u8,
+    chr_bank_1: u8,
+    prg_bank: u8,
+    cycle: u64,
+    last_write: Option<u64>,
+}
+
+impl MMC1 {
+    pub fn new(rom: Rom) -> Self {
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: PrgRam::new(rom.prg_ram_size, rom.prg_nvram_size),
+            chr: Chr::new(rom.chr_rom, rom.chr_ram_size + rom.chr_nvram_size),
+            shift_register: SHIFT_REGISTER_RESET,
+            control: 0b0_1100,
+            chr_bank_0: 0,
+            chr_bank_1: 0,
+            prg_bank: 0,
+            cycle: 0,
+            last_write: None,
+        }
+    }
+
+    fn write_register(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x8000..=0x9FFF => self.control = value,
+            0xA000..=0xBFFF => self.chr_bank_0 = value,
+            0xC000..=0xDFFF => self.chr_bank_1 = value,
+            0xE000..=0xFFFF => {
+                self.prg_bank = value;
+                // MMC1B and later disable PRG RAM with bit 4
+                self.prg_ram.set_enabled(value & 0b1_0000 == 0);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn prg_mode(&self) -> u8 {
+        (self.control >> 2) & 0b11
+    }
+
+    fn chr_mode(&self) -> u8 {
+        (self.control >> 4) & 1
+    }
+
+    fn prg_addr(&self, addr: u16) -> usize {
+        let addr = (addr - PROGRAM) as usize;
+        // SUROM boards select the 256 KB outer bank with bit 4 of the CHR bank register
+        let outer = if self.prg_rom.len() > 16 * PRG_ROM_PAGE_SIZE {
+            (self.chr_bank_0 & 0b1_0000) as usize
+        } else {
+            0
+        };
+        let bank = (self.prg_bank & 0b1111) as usize;
+        let last = (self.prg_rom.len() / PRG_ROM_PAGE_SIZE - 1) & 0b1111;
+
+        let bank = match (self.prg_mode(), addr / PRG_ROM_PAGE_SIZE) {
+            (0 | 1, half) => (bank & !1) + half,
+            (2, 0) => 0,
+            (2, _) => bank,
+            (3, 0) => bank,
+            (3, _) => last,
+            _ => unreachable!(),
+        };
+
+        (((outer | bank) * PRG_ROM_PAGE_SIZE) + addr % PRG_ROM_PAGE_SIZE) % self.prg_rom.len()
+    }
+
+    fn chr_addr(&self, addr: u16) -> usize {
+        let addr = addr as usize;
+
+        let bank = match (self.chr_mode(), addr / CHR_BANK_SIZE) {
+            (0, half) => (self.chr_bank_0 & !1) as usize + half,
+            (_, 0) => self.chr_bank_0 as usize,
+            (_, _) => self.chr_bank_1 as usize,
+        };
+
+        (bank * CHR_BANK_SIZE + addr % CHR_BANK_SIZE) % self.chr.len()
+    }
+}
+
+impl Mapper for MMC1 {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PRG_RAM..=PRG_RAM_END => self.prg_ram.read(addr),
+            PROGRAM..=0xFFFF => self.prg_rom[self.prg_addr(addr)],
+            _ => 0,
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        if addr < PROGRAM {
+            if (PRG_RAM..=PRG_RAM_END).contains(&addr) {
+                self.prg_ram.write(addr, data);
+            }
+            return;
+        }
+
+        // Writes on consecutive cycles (the double write of read-modify-write instructions)
+        // only register the first one
+        let consecutive = self
+            .last_write
+            .is_some_and(|last| self.cycle.saturating_sub(last) <= 1);
+        self.last_write = Some(self.cycle);
+        if consecutive {
+            return;
+        }
+
+        if data & 0b1000_0000 != 0 {
+            self.shift_register = SHIFT_REGISTER_RESET;
+            self.control |= 0b0_1100;
+            return;
+        }
+
+        let full = self.shift_register & 1 != 0;
+        self.shift_register = (self.shift_register >> 1) | ((data & 1) << 4);
+
+        if full {
+            self.write_register(addr, self.shift_register);
+            self.shift_register = SHIFT_REGISTER_RESET;
+        }
+    }
+
+    fn ppu_peek(&self, addr: u16) -> u8 {
+        self.chr[self.chr_addr(addr)]
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        self.chr.write(self.chr_addr(addr), data);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.control & 0b11 {
+            0 => Mirroring::SingleScreenA,
+            1 => Mirroring::SingleScreenB,
+            2 => Mirroring::Vertical,
+            3 => Mirroring::Horizontal,
+            _ => unreachable!(),
+        }
+    }
+
+    fn chr_ram(&self) -> &[u8] {
+        self.chr.ram()
+    }
+
+    fn chr_ram_mut(&mut self) -> &mut [u8] {
+        self.chr.ram_mut()
+    }
+
+    fn battery_ram(&self) -> &[u8] {
+        self.prg_ram.battery()
+    }
+
+    fn battery_ram_mut(&mut self) -> &mut [u8] {
+        self.prg_ram.battery_mut()
+    }
+
+    fn cpu_clock(&mut self) {
+        self.cycle += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::tests::mapper_rom;
+
+    use super::*;
+
+    fn write(mapper: &mut MMC1, addr: u16, value: u8) {
+        for bit in 0..5 {
+            mapper.cpu_write(addr, value >> bit);
+            mapper.cpu_clock();
+            mapper.cpu_clock();
+        }
+    }
+
+    #[test]
+    fn power_on() {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 8, 2));
+
+        // First bank at $8000, last bank fixed at $C000
+        assert_eq!(mapper.cpu_read(0x8000), 0);
+        assert_eq!(mapper.cpu_read(0xC000), 14);
+        assert_eq!(mapper.cpu_read(0xFFFF), 15);
+    }
+
+    #[test]
+    fn serial_load() {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 8, 2));
+
+        write(&mut mapper, 0xE000, 3);
+        assert_eq!(mapper.cpu_read(0x8000), 6);
+        assert_eq!(mapper.prg_bank, 3);
+        assert_eq!(mapper.shift_register, SHIFT_REGISTER_RESET);
+    }
+
+    #[test]
+    fn reset() {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 8, 2));
+        write(&mut mapper, 0x8000, 0b0_0000);
+
+        mapper.cpu_write(0xE000, 1);
+        mapper.cpu_clock();
+        mapper.cpu_clock();
+        mapper.cpu_write(0x8000, 0x80);
+        assert_eq!(mapper.shift_register, SHIFT_REGISTER_RESET);
+        assert_eq!(mapper.prg_mode(), 3);
+    }
+
+    #[test]
+    fn consecutive_writes() {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 8, 2));
+
+        mapper.cpu_write(0x8000, 1);
+        mapper.cpu_clock();
+        mapper.cpu_write(0x8000, 1);
+        assert_eq!(mapper.shift_register, 0b1_1000);
+    }
+
+    #[test_case(0, 0x8000, 8 ; "mode_0_low")]
+    #[test_case(0, 0xC000, 10 ; "mode_0_high")]
+    #[test_case(2, 0x8000, 0 ; "mode_2_fixed")]
+    #[test_case(2, 0xC000, 10 ; "mode_2_switch")]
+    #[test_case(3, 0x8000, 10 ; "mode_3_switch")]
+    #[test_case(3, 0xC000, 14 ; "mode_3_fixed")]
+    fn prg_modes(mode: u8, addr: u16, bank: u8) {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 8, 2));
+
+        write(&mut mapper, 0x8000, mode << 2);
+        write(&mut mapper, 0xE000, 5);
+        assert_eq!(mapper.cpu_read(addr), bank);
+    }
+
+    #[test]
+    fn chr_8kb_mode() {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 2, 2));
+
+        write(&mut mapper, 0x8000, 0b0_1100);
+        write(&mut mapper, 0xA000, 3);
+        assert_eq!(mapper.ppu_read(0x0000), 8);
+        assert_eq!(mapper.ppu_read(0x1000), 12);
+    }
+
+    #[test]
+    fn chr_4kb_mode() {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 2, 2));
+
+        write(&mut mapper, 0x8000, 0b1_1100);
+        write(&mut mapper, 0xA000, 3);
+        write(&mut mapper, 0xC000, 1);
+        assert_eq!(mapper.ppu_read(0x0000), 12);
+        assert_eq!(mapper.ppu_read(0x1000), 4);
+    }
+
+    #[test]
+    fn prg_ram_disable() {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 2, 2));
+        mapper.cpu_write(0x7000, 0x11);
+        assert_eq!(mapper.cpu_read(0x7000), 0x11);
+
+        write(&mut mapper, 0xE000, 0b1_0000);
+        assert_eq!(mapper.cpu_read(0x7000), 0);
+        write(&mut mapper, 0xE000, 0);
+        assert_eq!(mapper.cpu_read(0x7000), 0x11);
+    }
+
+    #[test_case(0, Mirroring::SingleScreenA ; "single_screen_a")]
+    #[test_case(1, Mirroring::SingleScreenB ; "single_screen_b")]
+    #[test_case(2, Mirroring::Vertical ; "vertical")]
+    #[test_case(3, Mirroring::Horizontal ; "horizontal")]
+    fn mirroring(value: u8, mirroring: Mirroring) {
+        let mut mapper = MMC1::new(mapper_rom(MMC1_MAPPER, 2, 2));
+
+        write(&mut mapper, 0x8000, value);
+        assert_eq!(mapper.mirroring(), mirroring);
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{Mirroring, Rom, PRG_RAM, PRG_RAM_END, PROGRAM};
+
+use super::{cartridge_vram, Chr, Mapper, PrgRam, CARTRIDGE_VRAM_SIZE};
+
+pub const MMC3_MAPPER: u16 = 4;
+
+const PRG_BANK_SIZE: usize = 0x2000;
+const CHR_BANK_SIZE: usize = 0x0400;
+const A12: u16 = 0x1000;
+/// A12 has to stay low for this many CPU cycles before a rising edge clocks the IRQ counter
+const A12_LOW_CYCLES: u8 = 3;
+
+/// The IRQ counter behaves differently depending on the chip revision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MMC3Revision {
+    /// MMC3B/MMC3C: an IRQ is raised on every clock that leaves the counter at 0
+    Sharp,
+    /// MMC3A: an IRQ is raised only when the counter is decremented or explicitly reloaded to 0
+    NEC,
+}
+
+/// Mapper 4 (TxROM).
+/// Switches 8 KB PRG ROM banks and 1-2 KB CHR banks, and counts scanlines by watching
+/// rising edges of PPU A12 to raise an IRQ.
+#[derive(Debug)]
+pub struct MMC3 {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+    /// Four-screen nametable RAM at $2800-$2FFF
+    vram: Vec<u8>,
+    revision: MMC3Revision,
+    /// 7  bit  0
+    /// ---- ----
+    /// CPMx xRRR
+    /// |||   |||
+    /// |||   +++- Specify which bank register to update on next write to Bank Data register
+    /// |||
+    /// ||+------- Nothing on the MMC3
+    /// |+-------- PRG ROM bank mode (0: $8000-$9FFF swappable, $C000-$DFFF fixed to second-last bank;
+    /// |                             1: $C000-$DFFF swappable, $8000-$9FFF fixed to second-last bank)
+    /// +--------- CHR A12 inversion (0: two 2 KB banks at $0000-$0FFF, four 1 KB banks at $1000-$1FFF;
+    ///                               1: two 2 KB banks at $1000-$1FFF, four 1 KB banks at $0000-$0FFF)
+    bank_select: u8,
+    registers: [u8; 8],
+    mirroring: Mirroring,
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq: bool,
+    a12: bool,
+    a12_low_cycles: u8,
+}
+
+impl MMC3 {
+    pub fn new(rom: Rom, revision: MMC3Revision) -> Self {
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: PrgRam::new(rom.prg_ram_size, rom.prg_nvram_size),
+            chr: Chr::new(rom.chr_rom, rom.chr_ram_size + rom.chr_nvram_size),
+            vram: cartridge_vram(rom.screen_mirroring),
+            revision,
+            bank_select: 0,
+            registers: [0; 8],
+            mirroring: rom.screen_mirroring,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq: false,
+            a12: false,
+            a12_low_cycles: 0,
+        }
+    }
+
+    fn prg_addr(&self, addr: u16) -> usize {
+        let addr = (addr - PROGRAM) as usize;
+        let banks = self.prg_rom.len() / PRG_BANK_SIZE;
+        let second_last = banks - 2;
+        let last = banks - 1;
+        let r6 = self.registers[6] as usize & 0b0011_1111;
+        let r7 = self.registers[7] as usize & 0b0011_1111;
+
+        let bank = match (self.bank_select & 0b0100_0000 != 0, addr / PRG_BANK_SIZE) {
+            (false, 0) => r6,
+            (true, 0) => second_last,
+            (_, 1) => r7,
+            (false, 2) => second_last,
+            (true, 2) => r6,
+            (_, _) => last,
+        };
+
+        (bank % banks) * PRG_BANK_SIZE + addr % PRG_BANK_SIZE
+    }
+
+    fn chr_addr(&self, addr: u16) -> usize {
+        let inverted = self.bank_select & 0b1000_0000 != 0;
+        let addr = (addr ^ if inverted { A12 } else { 0 }) as usize;
+
+        let bank = match addr / CHR_BANK_SIZE {
+            0 => self.registers[0] & !1,
+            1 => self.registers[0] | 1,
+            2 => self.registers[1] & !1,
+            3 => self.registers[1] | 1,
+            slot => self.registers[slot - 2],
+        } as usize;
+
+        (bank * CHR_BANK_SIZE + addr % CHR_BANK_SIZE) % self.chr.len()
+    }
+
+    fn clock_irq_counter(&mut self) {
+        let before = self.irq_counter;
+        let reload = self.irq_reload;
+
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+        } else {
+            self.irq_counter -= 1;
+        }
+        self.irq_reload = false;
+
+        let trigger = match self.revision {
+            MMC3Revision::Sharp => self.irq_counter == 0,
+            MMC3Revision::NEC => self.irq_counter == 0 && (before != 0 || reload),
+        };
+
+        if trigger && self.irq_enabled {
+            self.irq = true;
+        }
+    }
+}
+
+impl Mapper for MMC3 {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PRG_RAM..=PRG_RAM_END => self.prg_ram.read(addr),
+            PROGRAM..=0xFFFF => self.prg_rom[self.prg_addr(addr)],
+            _ => 0,
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        let even = addr & 1 == 0;
+
+        match (addr, even) {
+            (PRG_RAM..=PRG_RAM_END, _) => self.prg_ram.write(addr, data),
+            (0x8000..=0x9FFF, true) => self.bank_select = data,
+            (0x8000..=0x9FFF, false) => {
+                self.registers[(self.bank_select & 0b111) as usize] = data;
+            }
+            (0xA000..=0xBFFF, true) if self.mirroring != Mirroring::FourScreen => {
+                self.mirroring = if data & 1 == 0 {
+                    Mirroring::Vertical
+                } else {
+                    Mirroring::Horizontal
+                };
+            }
+            (0xA000..=0xBFFF, false) => {
+                self.prg_ram.set_enabled(data & 0b1000_0000 != 0);
+                self.prg_ram.set_write_protected(data & 0b0100_0000 != 0);
+            }
+            (0xA000..=0xBFFF, true) => {}
+            (0xC000..=0xDFFF, true) => self.irq_latch = data,
+            (0xC000..=0xDFFF, false) => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            }
+            (0xE000..=0xFFFF, true) => {
+                self.irq_enabled = false;
+                self.irq = false;
+            }
+            (0xE000..=0xFFFF, false) => self.irq_enabled = true,
+            _ => {}
+        }
+    }
+
+    fn ppu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x2000.. => self.vram[addr as usize % CARTRIDGE_VRAM_SIZE],
+            _ => self.chr[self.chr_addr(addr)],
+        }
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x2000.. => self.vram[addr as usize % CARTRIDGE_VRAM_SIZE] = data,
+            _ => self.chr.write(self.chr_addr(addr), data),
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn chr_ram(&self) -> &[u8] {
+        self.chr.ram()
+    }
+
+    fn chr_ram_mut(&mut self) -> &mut [u8] {
+        self.chr.ram_mut()
+    }
+
+    fn battery_ram(&self) -> &[u8] {
+        self.prg_ram.battery()
+    }
+
+    fn battery_ram_mut(&mut self) -> &mut [u8] {
+        self.prg_ram.battery_mut()
+    }
+
+    fn irq(&self) -> bool {
+        self.irq
+    }
+
+    fn cpu_clock(&mut self) {
+        if !self.a12 {
+            self.a12_low_cycles = self.a12_low_cycles.saturating_add(1);
+        }
+    }
+
+    fn ppu_bus_address(&mut self, addr: u16) {
+        let a12 = addr & A12 != 0;
+
+        if a12 && !self.a12 && self.a12_low_cycles >= A12_LOW_CYCLES {
+            self.clock_irq_counter();
+        }
+        if !a12 && self.a12 {
+            self.a12_low_cycles = 0;
+        }
+
+        self.a12 = a12;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::tests::mapper_rom;
+
+    use super::*;
+
+    fn scanline(mapper: &mut MMC3) {
+        for _ in 0..A12_LOW_CYCLES {
+            mapper.cpu_clock();
+        }
+        mapper.ppu_bus_address(0x1000);
+        mapper.ppu_bus_address(0x0000);
+    }
+
+    #[test_case(false, 0x8000, 4 ; "mode_0_r6")]
+    #[test_case(false, 0xA000, 5 ; "mode_0_r7")]
+    #[test_case(false, 0xC000, 14 ; "mode_0_second_last")]
+    #[test_case(false, 0xE000, 15 ; "mode_0_last")]
+    #[test_case(true, 0x8000, 14 ; "mode_1_second_last")]
+    #[test_case(true, 0xA000, 5 ; "mode_1_r7")]
+    #[test_case(true, 0xC000, 4 ; "mode_1_r6")]
+    #[test_case(true, 0xE000, 15 ; "mode_1_last")]
+    fn prg_banks(mode: bool, addr: u16, bank: u8) {
+        let mut mapper = MMC3::new(mapper_rom(MMC3_MAPPER, 8, 1), MMC3Revision::Sharp);
+
+        let mode = (mode as u8) << 6;
+        mapper.cpu_write(0x8000, mode | 6);
+        mapper.cpu_write(0x8001, 4);
+        mapper.cpu_write(0x8000, mode | 7);
+        mapper.cpu_write(0x8001, 5);
+        assert_eq!(mapper.cpu_read(addr), bank);
+    }
+
+    #[test_case(false, 0x0000, 2 ; "normal_2kb_low")]
+    #[test_case(false, 0x0400, 3 ; "normal_2kb_high")]
+    #[test_case(false, 0x1000, 7 ; "normal_1kb")]
+    #[test_case(true, 0x1000, 2 ; "inverted_2kb_low")]
+    #[test_case(true, 0x1400, 3 ; "inverted_2kb_high")]
+    #[test_case(true, 0x0000, 7 ; "inverted_1kb")]
+    fn chr_banks(inverted: bool, addr: u16, bank: u8) {
+        let mut mapper = MMC3::new(mapper_rom(MMC3_MAPPER, 2, 1), MMC3Revision::Sharp);
+
+        let inversion = (inverted as u8) << 7;
+        mapper.cpu_write(0x8000, inversion);
+        mapper.cpu_write(0x8001, 3);
+        mapper.cpu_write(0x8000, inversion | 2);
+        mapper.cpu_write(0x8001, 7);
+        assert_eq!(mapper.ppu_read(addr), bank);
+    }
+
+    #[test]
+    fn mirroring() {
+        let mut mapper = MMC3::new(mapper_rom(MMC3_MAPPER, 2, 1), MMC3Revision::Sharp);
+
+        mapper.cpu_write(0xA000, 1);
+        assert_eq!(mapper.mirroring(), Mirroring::Horizontal);
+        mapper.cpu_write(0xA000, 0);
+        assert_eq!(mapper.mirroring(), Mirroring::Vertical);
+    }
+
+    #[test]
+    fn prg_ram_protection() {
+        let mut mapper = MMC3::new(mapper_rom(MMC3_MAPPER, 2, 1), MMC3Revision::Sharp);
+        mapper.cpu_write(0x6000, 0x11);
+        assert_eq!(mapper.cpu_read(0x6000), 0x11);
+
+        // Enabled, write protected
+        mapper.cpu_write(0xA001, 0b1100_0000);
+        mapper.cpu_write(0x6000, 0x22);
+        assert_eq!(mapper.cpu_read(0x6000), 0x11);
+
+        // Disabled
+        mapper.cpu_write(0xA001, 0);
+        assert_eq!(mapper.cpu_read(0x6000), 0);
+    }
+
+    #[test]
+    fn irq_counter() {
+        let mut mapper = MMC3::new(mapper_rom(MMC3_MAPPER, 2, 1), MMC3Revision::Sharp);
+        mapper.cpu_write(0xC000, 2);
+        mapper.cpu_write(0xC001, 0);
+        mapper.cpu_write(0xE001, 0);
+
+        scanline(&mut mapper);
+        assert_eq!(mapper.irq_counter, 2);
+        scanline(&mut mapper);
+        assert!(!mapper.irq());
+        scanline(&mut mapper);
+        assert!(mapper.irq());
+
+        // Acknowledge
+        mapper.cpu_write(0xE000, 0);
+        assert!(!mapper.irq());
+    }
+
+    #[test]
+    fn a12_filter() {
+        let mut mapper = MMC3::new(mapper_rom(MMC3_MAPPER, 2, 1), MMC3Revision::Sharp);
+        mapper.cpu_write(0xC000, 5);
+        mapper.cpu_write(0xC001, 0);
+
+        scanline(&mut mapper);
+        assert_eq!(mapper.irq_counter, 5);
+
+        // Sprite fetches toggle A12 faster than the filter lets through
+        mapper.cpu_clock();
+        mapper.ppu_bus_address(0x1000);
+        assert_eq!(mapper.irq_counter, 5);
+    }
+
+    #[test_case(MMC3Revision::Sharp, true ; "sharp")]
+    #[test_case(MMC3Revision::NEC, false ; "nec")]
+    fn zero_latch(revision: MMC3Revision, irq: bool) {
+        let mut mapper = MMC3::new(mapper_rom(MMC3_MAPPER, 2, 1), revision);
+        mapper.cpu_write(0xC000, 0);
+        mapper.cpu_write(0xE001, 0);
+
+        // The counter is reloaded with 0 on every clock
+        scanline(&mut mapper);
+        scanline(&mut mapper);
+        assert_eq!(mapper.irq(), irq);
+    }
+}
@@ -0,0 +1,393 @@
+pub mod axrom;
+pub mod cnrom;
+pub mod mmc1;
+pub mod mmc3;
+pub mod nrom;
+pub mod uxrom;
+
+pub use axrom::*;
+pub use cnrom::*;
+pub use mmc1::*;
+pub use mmc3::*;
+pub use nrom::*;
+pub use uxrom::*;
+
+use core::{cell::RefCell, fmt::Debug, ops::Deref};
+use std::rc::Rc;
+
+use crate::{
+    Mirroring, Rom, CHR_ROM_PAGE_SIZE, PRG_RAM, PRG_RAM_PAGE_SIZE, PRG_ROM_PAGE_SIZE, TRAINER,
+};
+
+/// Size of the extra nametable RAM on four-screen boards
+pub const CARTRIDGE_VRAM_SIZE: usize = 0x800;
+
+/// Pattern table memory of the board: CHR ROM, or CHR RAM when the header declares none
+#[derive(Debug, Clone)]
+pub struct Chr {
+    memory: Vec<u8>,
+    ram: bool,
+}
+
+impl Chr {
+    pub fn new(chr_rom: Vec<u8>, chr_ram_size: usize) -> Self {
+        if chr_rom.is_empty() {
+            Self {
+                memory: vec![0; chr_ram_size],
+                ram: true,
+            }
+        } else {
+            Self {
+                memory: chr_rom,
+                ram: false,
+            }
+        }
+    }
+
+    /// Write to CHR RAM, ignored by CHR ROM
+    pub fn write(&mut self, index: usize, data: u8) {
+        if self.ram {
+            self.memory[index] = data;
+        }
+    }
+
+    /// CHR RAM contents, empty for CHR ROM
+    pub fn ram(&self) -> &[u8] {
+        if self.ram {
+            &self.memory
+        } else {
+            &[]
+        }
+    }
+
+    pub fn ram_mut(&mut self) -> &mut [u8] {
+        if self.ram {
+            &mut self.memory
+        } else {
+            &mut []
+        }
+    }
+}
+
+impl Deref for Chr {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.memory
+    }
+}
+
+/// Work RAM at $6000-$7FFF, kept between sessions when battery-backed.
+///
+/// Mappers can disable the chip, making reads return 0 and ignoring writes,
+/// or protect it from writes while leaving it readable.
+#[derive(Debug, Clone)]
+pub struct PrgRam {
+    memory: Vec<u8>,
+    battery: bool,
+    enabled: bool,
+    write_protected: bool,
+}
+
+impl PrgRam {
+    /// Volatile and battery-backed RAM, a board with both keeps all of it in save files
+    pub fn new(size: usize, nvram_size: usize) -> Self {
+        Self {
+            memory: vec![0; size + nvram_size],
+            battery: nvram_size > 0,
+            enabled: true,
+            write_protected: false,
+        }
+    }
+
+    /// Read from $6000-$7FFF, chips smaller than 8 KB are mirrored
+    pub fn read(&self, addr: u16) -> u8 {
+        if !self.enabled || self.memory.is_empty() {
+            return 0;
+        }
+
+        self.memory[(addr - PRG_RAM) as usize % self.memory.len()]
+    }
+
+    /// Write to $6000-$7FFF, ignored while disabled or write protected
+    pub fn write(&mut self, addr: u16, data: u8) {
+        if !self.enabled || self.write_protected || self.memory.is_empty() {
+            return;
+        }
+
+        let len = self.memory.len();
+        self.memory[(addr - PRG_RAM) as usize % len] = data;
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn set_write_protected(&mut self, write_protected: bool) {
+        self.write_protected = write_protected;
+    }
+
+    /// Contents to persist in save files, empty without a battery
+    pub fn battery(&self) -> &[u8] {
+        if self.battery {
+            &self.memory
+        } else {
+            &[]
+        }
+    }
+
+    pub fn battery_mut(&mut self) -> &mut [u8] {
+        if self.battery {
+            &mut self.memory
+        } else {
+            &mut []
+        }
+    }
+}
+
+/// Where a nametable access in $2000-$3EFF lands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nametable {
+    /// Offset into the 2 KB of console VRAM
+    Console(u16),
+    /// Console VRAM is disabled and the cartridge answers through `ppu_read`/`ppu_write`,
+    /// from its own VRAM, ExRAM or CHR ROM
+    Cartridge,
+}
+
+/// Cartridge hardware, owning the PRG and CHR memory of the board.
+///
+/// The CPU bus forwards every access in $4020-$FFFF and the PPU forwards every
+/// pattern table access in $0000-$1FFF, so the mapper is free to bank switch,
+/// expose registers or change the nametable mapping at runtime.
+pub trait Mapper: Debug {
+    /// Read from CPU address space $4020-$FFFF
+    fn cpu_read(&mut self, addr: u16) -> u8 {
+        self.cpu_peek(addr)
+    }
+
+    /// The value `cpu_read` would return, without any side effect
+    fn cpu_peek(&self, addr: u16) -> u8;
+
+    /// Write to CPU address space $4020-$FFFF
+    fn cpu_write(&mut self, addr: u16, data: u8);
+
+    /// Read from PPU address space $0000-$1FFF,
+    /// and $2000-$3EFF when `nametable` hands the access to the cartridge
+    fn ppu_read(&mut self, addr: u16) -> u8 {
+        self.ppu_peek(addr)
+    }
+
+    /// The value `ppu_read` would return, without any side effect
+    fn ppu_peek(&self, addr: u16) -> u8;
+
+    /// Write to PPU address space $0000-$1FFF,
+    /// and $2000-$3EFF when `nametable` hands the access to the cartridge
+    fn ppu_write(&mut self, addr: u16, data: u8);
+
+    /// Current nametable arrangement
+    fn mirroring(&self) -> Mirroring;
+
+    /// Where the nametable access at `addr` ($2000-$3EFF) lands,
+    /// by default following the current mirroring
+    fn nametable(&self, addr: u16) -> Nametable {
+        self.mirroring().nametable(addr)
+    }
+
+    /// CHR RAM contents to keep in save states, empty on boards with CHR ROM.
+    /// Nothing reads it yet, it is the cartridge side of the save states to come
+    fn chr_ram(&self) -> &[u8] {
+        &[]
+    }
+
+    /// CHR RAM contents to restore from save states, empty on boards with CHR ROM
+    fn chr_ram_mut(&mut self) -> &mut [u8] {
+        &mut []
+    }
+
+    /// Battery-backed PRG RAM to persist in save files, empty on boards without a battery
+    fn battery_ram(&self) -> &[u8] {
+        &[]
+    }
+
+    /// Battery-backed PRG RAM to restore from save files, empty on boards without a battery
+    fn battery_ram_mut(&mut self) -> &mut [u8] {
+        &mut []
+    }
+
+    /// Whether the cartridge is asserting the CPU IRQ line
+    fn irq(&self) -> bool {
+        false
+    }
+
+    /// Called once per CPU cycle (M2)
+    fn cpu_clock(&mut self) {}
+
+    /// Called whenever the PPU drives an address on its bus,
+    /// letting the cartridge watch pattern table fetches (e.g. rising edges of A12)
+    fn ppu_bus_address(&mut self, _addr: u16) {}
+}
+
+/// Extra nametable RAM backing $2800-$2FFF, present on four-screen boards only
+fn cartridge_vram(mirroring: Mirroring) -> Vec<u8> {
+    match mirroring {
+        Mirroring::FourScreen => vec![0; CARTRIDGE_VRAM_SIZE],
+        _ => Vec::new(),
+    }
+}
+
+/// The mapper is shared between the CPU bus and the PPU
+pub type SharedMapper = Rc<RefCell<dyn Mapper>>;
+
+/// Build the cartridge hardware selected by `Rom::mapper`
+pub fn new_mapper(mut rom: Rom) -> Result<SharedMapper, MapperError> {
+    // Every board switches PRG ROM in 8-32 KB banks and maps 8 KB of CHR at $0000-$1FFF
+    if rom.prg_rom.is_empty() || !rom.prg_rom.len().is_multiple_of(PRG_ROM_PAGE_SIZE) {
+        return Err(MapperError::PrgRomSize(rom.prg_rom.len()));
+    }
+    let chr_size = match rom.chr_rom.len() {
+        0 => rom.chr_ram_size + rom.chr_nvram_size,
+        len => len,
+    };
+    if chr_size == 0 || !chr_size.is_multiple_of(CHR_ROM_PAGE_SIZE) {
+        return Err(MapperError::ChrSize(chr_size));
+    }
+
+    let trainer = rom.trainer.take();
+    // The trainer is loaded in PRG RAM, which NES 2.0 headers may leave out
+    if trainer.is_some() && rom.prg_ram_size + rom.prg_nvram_size == 0 {
+        rom.prg_ram_size = PRG_RAM_PAGE_SIZE;
+    }
+    // NES 2.0 submapper 2 of discrete logic boards (UxROM, CNROM, AxROM) has bus conflicts,
+    // submapper 1 hasn't and iNES 1.0 headers can't tell them apart
+    let bus_conflicts = rom.submapper == 2;
+    // NES 2.0 submapper 4 of mapper 4 is the MMC3A
+    let mmc3_revision = match rom.submapper {
+        4 => MMC3Revision::NEC,
+        _ => MMC3Revision::Sharp,
+    };
+
+    let mapper: SharedMapper = match rom.mapper {
+        NROM_MAPPER => Rc::new(RefCell::new(NROM::new(rom))),
+        MMC1_MAPPER => Rc::new(RefCell::new(MMC1::new(rom))),
+        UXROM_MAPPER => Rc::new(RefCell::new(UxROM::new(rom, bus_conflicts))),
+        CNROM_MAPPER => Rc::new(RefCell::new(CNROM::new(rom, bus_conflicts))),
+        AXROM_MAPPER => Rc::new(RefCell::new(AxROM::new(rom, bus_conflicts))),
+        MMC3_MAPPER => Rc::new(RefCell::new(MMC3::new(rom, mmc3_revision))),
+        mapper => return Err(MapperError::Unsupported(mapper)),
+    };
+
+    if let Some(trainer) = trainer {
+        let mut mapper = mapper.borrow_mut();
+        for (addr, data) in (TRAINER..).zip(trainer) {
+            mapper.cpu_write(addr, data);
+        }
+    }
+
+    Ok(mapper)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MapperError {
+    #[error("Mapper {0} is not supported")]
+    Unsupported(u16),
+    #[error("PRG ROM of {0} bytes, expected a non-zero multiple of 16 KB")]
+    PrgRomSize(usize),
+    #[error("CHR memory of {0} bytes, expected a non-zero multiple of 8 KB")]
+    ChrSize(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::tests::{mapper_rom, test_rom};
+
+    use super::*;
+
+    #[test]
+    fn unsupported() {
+        let mut rom = test_rom(&[]);
+        rom.mapper = 0xFF;
+
+        assert!(matches!(
+            new_mapper(rom),
+            Err(MapperError::Unsupported(0xFF))
+        ));
+    }
+
+    #[test_case(0 ; "empty")]
+    #[test_case(0x2000 ; "too_small")]
+    #[test_case(0x6000 ; "partial_bank")]
+    fn prg_rom_size(len: usize) {
+        for mapper in [
+            NROM_MAPPER,
+            MMC1_MAPPER,
+            UXROM_MAPPER,
+            CNROM_MAPPER,
+            AXROM_MAPPER,
+            MMC3_MAPPER,
+        ] {
+            let mut rom = mapper_rom(mapper, 2, 1);
+            rom.prg_rom.truncate(len);
+
+            assert!(matches!(new_mapper(rom), Err(MapperError::PrgRomSize(size)) if size == len));
+        }
+    }
+
+    #[test_case(0, 0 ; "no_chr_rom_nor_ram")]
+    #[test_case(0x400, 0 ; "chr_rom_too_small")]
+    #[test_case(0, 0x1000 ; "chr_ram_too_small")]
+    fn chr_size(chr_rom_size: usize, chr_ram_size: usize) {
+        for mapper in [
+            NROM_MAPPER,
+            MMC1_MAPPER,
+            UXROM_MAPPER,
+            CNROM_MAPPER,
+            AXROM_MAPPER,
+            MMC3_MAPPER,
+        ] {
+            let mut rom = mapper_rom(mapper, 2, 1);
+            rom.chr_rom.truncate(chr_rom_size);
+            rom.chr_ram_size = chr_ram_size;
+
+            assert!(matches!(new_mapper(rom), Err(MapperError::ChrSize(_))));
+        }
+    }
+
+    #[test_case(0, 14 ; "unknown")]
+    #[test_case(1, 14 ; "without_bus_conflicts")]
+    #[test_case(2, 12 ; "with_bus_conflicts")]
+    fn submapper_bus_conflicts(submapper: u8, bank: u8) {
+        let mut rom = mapper_rom(UXROM_MAPPER, 8, 1);
+        rom.submapper = submapper;
+        let mapper = new_mapper(rom).unwrap();
+
+        // $C000 holds 14 (0b1110), writing 7 (0b0111) selects bank 6 with bus conflicts
+        mapper.borrow_mut().cpu_write(0xC000, 7);
+        assert_eq!(mapper.borrow_mut().cpu_read(0x8000), bank);
+    }
+
+    #[test]
+    fn trainer() {
+        let mut rom = test_rom(&[]);
+        rom.trainer = Some(vec![0xAB; 512]);
+        let mapper = new_mapper(rom).unwrap();
+
+        assert_eq!(mapper.borrow().cpu_peek(0x6FFF), 0);
+        assert_eq!(mapper.borrow().cpu_peek(0x7000), 0xAB);
+        assert_eq!(mapper.borrow().cpu_peek(0x71FF), 0xAB);
+        assert_eq!(mapper.borrow().cpu_peek(0x7200), 0);
+    }
+
+    #[test]
+    fn trainer_without_prg_ram() {
+        let mut rom = test_rom(&[]);
+        rom.prg_ram_size = 0;
+        rom.trainer = Some(vec![0xAB; 512]);
+        let mapper = new_mapper(rom).unwrap();
+
+        assert_eq!(mapper.borrow().cpu_peek(0x7000), 0xAB);
+        assert_eq!(mapper.borrow().cpu_peek(0x71FF), 0xAB);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Mirroring, Rom, PRG_RAM, PRG_RAM_END, PRG_ROM_PAGE_SIZE, PROGRAM};
+
+use super::{cartridge_vram, Chr, Mapper, PrgRam, CARTRIDGE_VRAM_SIZE};
+
+pub const NROM_MAPPER: u16 = 0;
+
+/// Mapper 0, no bank switching.
+/// PRG ROM is either 16 KB (mirrored into $C000-$FFFF) or 32 KB, CHR is a single 8 KB bank.
+#[derive(Debug)]
+pub struct NROM {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+    /// Four-screen nametable RAM at $2800-$2FFF
+    vram: Vec<u8>,
+    mirroring: Mirroring,
+}
+
+impl NROM {
+    pub fn new(rom: Rom) -> Self {
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: PrgRam::new(rom.prg_ram_size, rom.prg_nvram_size),
+            chr: Chr::new(rom.chr_rom, rom.chr_ram_size + rom.chr_nvram_size),
+            vram: cartridge_vram(rom.screen_mirroring),
+            mirroring: rom.screen_mirroring,
+        }
+    }
+}
+
+impl Mapper for NROM {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PRG_RAM..=PRG_RAM_END => self.prg_ram.read(addr),
+            PROGRAM..=0xFFFF => {
+                let mut addr = (addr - PROGRAM) as usize;
+                if self.prg_rom.len() == PRG_ROM_PAGE_SIZE {
+                    // mirror if needed
+                    addr %= PRG_ROM_PAGE_SIZE;
+                }
+                self.prg_rom[addr]
+            }
+            _ => 0,
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) {
+        if (PRG_RAM..=PRG_RAM_END).contains(&addr) {
+            self.prg_ram.write(addr, data);
+        }
+    }
+
+    fn ppu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x2000.. => self.vram[addr as usize % CARTRIDGE_VRAM_SIZE],
+            _ => self.chr[addr as usize],
+        }
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x2000.. => self.vram[addr as usize % CARTRIDGE_VRAM_SIZE] = data,
+            _ => self.chr.write(addr as usize, data),
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn chr_ram(&self) -> &[u8] {
+        self.chr.ram()
+    }
+
+    fn chr_ram_mut(&mut self) -> &mut [u8] {
+        self.chr.ram_mut()
+    }
+
+    fn battery_ram(&self) -> &[u8] {
+        self.prg_ram.battery()
+    }
+
+    fn battery_ram_mut(&mut self) -> &mut [u8] {
+        self.prg_ram.battery_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::test_rom;
+
+    use super::*;
+
+    #[test]
+    fn mirrors_16kb_prg_rom() {
+        let mut rom = test_rom(&[0xAA]);
+        rom.prg_rom.truncate(PRG_ROM_PAGE_SIZE);
+        let mut mapper = NROM::new(rom);
+
+        assert_eq!(mapper.cpu_read(0x8000), 0xAA);
+        assert_eq!(mapper.cpu_read(0xC000), 0xAA);
+    }
+}
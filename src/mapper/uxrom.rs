@@ -0,0 +1,151 @@
+use crate::{Mirroring, Rom, PRG_RAM, PRG_RAM_END, PRG_ROM_PAGE_SIZE, PROGRAM};
+
+use super::{cartridge_vram, Chr, Mapper, PrgRam, CARTRIDGE_VRAM_SIZE};
+
+pub const UXROM_MAPPER: u16 = 2;
+
+/// Mapper 2 (UNROM, UOROM).
+/// A 16 KB PRG ROM bank is switched at $8000-$BFFF and the last bank is fixed at $C000-$FFFF.
+#[derive(Debug)]
+pub struct UxROM {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+    /// Four-screen nametable RAM at $2800-$2FFF
+    vram: Vec<u8>,
+    mirroring: Mirroring,
+    prg_bank: u8,
+    bus_conflicts: bool,
+}
+
+impl UxROM {
+    pub fn new(rom: Rom, bus_conflicts: bool) -> Self {
+        Self {
+            prg_rom: rom.prg_rom,
+            prg_ram: PrgRam::new(rom.prg_ram_size, rom.prg_nvram_size),
+            chr: Chr::new(rom.chr_rom, rom.chr_ram_size + rom.chr_nvram_size),
+            vram: cartridge_vram(rom.screen_mirroring),
+            mirroring: rom.screen_mirroring,
+            prg_bank: 0,
+            bus_conflicts,
+        }
+    }
+
+    fn prg_addr(&self, addr: u16) -> usize {
+        let addr = (addr - PROGRAM) as usize;
+        let banks = self.prg_rom.len() / PRG_ROM_PAGE_SIZE;
+
+        let bank = match addr / PRG_ROM_PAGE_SIZE {
+            0 => self.prg_bank as usize % banks,
+            _ => banks - 1,
+        };
+
+        bank * PRG_ROM_PAGE_SIZE + addr % PRG_ROM_PAGE_SIZE
+    }
+}
+
+impl Mapper for UxROM {
+    fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            PRG_RAM..=PRG_RAM_END => self.prg_ram.read(addr),
+            PROGRAM..=0xFFFF => self.prg_rom[self.prg_addr(addr)],
+            _ => 0,
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, mut data: u8) {
+        if addr < PROGRAM {
+            if (PRG_RAM..=PRG_RAM_END).contains(&addr) {
+                self.prg_ram.write(addr, data);
+            }
+            return;
+        }
+
+        if self.bus_conflicts {
+            data &= self.cpu_peek(addr);
+        }
+
+        self.prg_bank = data;
+    }
+
+    fn ppu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x2000.. => self.vram[addr as usize % CARTRIDGE_VRAM_SIZE],
+            _ => self.chr[addr as usize],
+        }
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x2000.. => self.vram[addr as usize % CARTRIDGE_VRAM_SIZE] = data,
+            _ => self.chr.write(addr as usize, data),
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn chr_ram(&self) -> &[u8] {
+        self.chr.ram()
+    }
+
+    fn chr_ram_mut(&mut self) -> &mut [u8] {
+        self.chr.ram_mut()
+    }
+
+    fn battery_ram(&self) -> &[u8] {
+        self.prg_ram.battery()
+    }
+
+    fn battery_ram_mut(&mut self) -> &mut [u8] {
+        self.prg_ram.battery_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mapper_rom;
+
+    use super::*;
+
+    #[test]
+    fn switch_bank() {
+        let mut mapper = UxROM::new(mapper_rom(UXROM_MAPPER, 8, 1), false);
+        assert_eq!(mapper.cpu_read(0x8000), 0);
+        assert_eq!(mapper.cpu_read(0xC000), 14);
+
+        mapper.cpu_write(0x8000, 3);
+        assert_eq!(mapper.cpu_read(0x8000), 6);
+        assert_eq!(mapper.cpu_read(0xBFFF), 7);
+        assert_eq!(mapper.cpu_read(0xC000), 14);
+    }
+
+    #[test]
+    fn bus_conflicts() {
+        let mut mapper = UxROM::new(mapper_rom(UXROM_MAPPER, 8, 1), true);
+
+        // $C000 holds 14 (0b1110), so writing 7 (0b0111) selects bank 6
+        mapper.cpu_write(0xC000, 7);
+        assert_eq!(mapper.cpu_read(0x8000), 12);
+    }
+
+    #[test]
+    fn chr_ram() {
+        let mut mapper = UxROM::new(mapper_rom(UXROM_MAPPER, 8, 0), false);
+
+        mapper.ppu_write(0x1FFF, 0xAB);
+        assert_eq!(mapper.ppu_read(0x1FFF), 0xAB);
+        assert_eq!(mapper.chr_ram().len(), 0x2000);
+        assert_eq!(mapper.chr_ram()[0x1FFF], 0xAB);
+    }
+
+    #[test]
+    fn chr_rom_is_read_only() {
+        let mut mapper = UxROM::new(mapper_rom(UXROM_MAPPER, 8, 1), false);
+
+        mapper.ppu_write(0x0400, 0xAB);
+        assert_eq!(mapper.ppu_read(0x0400), 1);
+        assert!(mapper.chr_ram().is_empty());
+    }
+}
@@ -3,6 +3,16 @@ pub trait Mem {
 
     fn mem_write(&mut self, addr: u16, data: u8);
 
+    /// The value `mem_read` would return, without any side effect.
+    /// Meant for tracing, debuggers and memory viewers
+    fn peek(&self, addr: u16) -> u8;
+
+    fn peek_u16(&self, pos: u16) -> u16 {
+        let lo = self.peek(pos);
+        let hi = self.peek(pos.wrapping_add(1));
+        u16::from_le_bytes([lo, hi])
+    }
+
     fn mem_read_u16(&mut self, pos: u16) -> u16 {
         let lo = self.mem_read(pos);
         let hi = self.mem_read(pos.wrapping_add(1));
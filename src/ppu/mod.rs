@@ -1,59 +1,79 @@
+pub mod frame;
+pub mod palette;
 pub mod registers;
+mod render;
 
-use crate::Mirroring;
+pub use frame::*;
+pub use palette::*;
+pub use render::*;
+
+use crate::{Nametable, SharedMapper};
 use registers::*;
 
 #[derive(Debug)]
 pub struct PPU {
-    pub chr_rom: Vec<u8>,
+    pub mapper: SharedMapper,
     pub palette_table: [u8; 32],
     pub vram: [u8; 2048],
     pub oam_data: [u8; 256],
-    pub mirroring: Mirroring,
     pub ctrl: ControlRegister,
     pub mask: MaskRegister,
     pub status: StatusRegister,
     pub oam_addr: OAMAddressRegister,
     pub oam_data_r: OAMDataRegister,
-    pub scroll: ScrollRegister,
-    pub addr: AddressRegister,
+    /// Shared scroll and VRAM address registers
+    pub internal: InternalRegisters,
     pub data: DataRegister,
     pub oamdma: OAMDMARegister,
     internal_data_buf: u8,
     pub scanline: u16,
     pub cycles: usize,
     nmi_interrupt: Option<()>,
+    /// $2002 was read on the dot before vblank, the flag and NMI are skipped this frame
+    suppress_vblank: bool,
+    pub frame: Frame,
+    pipeline: Pipeline,
+    odd_frame: bool,
 }
 
 impl PPU {
-    pub fn new(chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+    pub fn new(mapper: SharedMapper) -> Self {
         Self {
-            chr_rom,
+            mapper,
             palette_table: [0; 32],
             vram: [0; 2048],
             oam_data: [0; 256],
-            mirroring,
             ctrl: ControlRegister::new(),
             mask: MaskRegister::new(),
             status: StatusRegister::new(),
             oam_addr: OAMAddressRegister::new(),
             oam_data_r: OAMDataRegister::new(),
-            scroll: ScrollRegister::new(),
-            addr: AddressRegister::new(),
+            internal: InternalRegisters::new(),
             data: DataRegister::new(),
             oamdma: OAMDMARegister::new(),
             internal_data_buf: 0,
             scanline: 0,
             cycles: 21,
             nmi_interrupt: None,
+            suppress_vblank: false,
+            frame: Frame::new(),
+            pipeline: Pipeline::default(),
+            odd_frame: false,
         }
     }
 
     pub fn write_to_ctrl(&mut self, value: u8) {
         let before = self.ctrl.generate_vblank_nmi();
         self.ctrl.update(value);
-        if !before && self.ctrl.generate_vblank_nmi() && self.status.is_in_vblank() {
-            self.nmi_interrupt = Some(());
+        self.internal.write_ctrl(value);
+
+        match (before, self.ctrl.generate_vblank_nmi()) {
+            // The NMI output is vblank AND enable, so enabling it during vblank
+            // raises another edge, as many times as it is toggled
+            (false, true) if self.status.is_in_vblank() => self.nmi_interrupt = Some(()),
+            // Disabling it right as vblank starts drops the output before the CPU sees it
+            (true, false) if self.vblank_just_started() => self.nmi_interrupt = None,
+            _ => {}
         }
     }
 
@@ -63,12 +83,30 @@ impl PPU {
 
     pub fn read_status(&mut self) -> u8 {
         let data = self.status.bits();
+
+        if (self.scanline, self.cycles) == (241, 0) {
+            // Reading one dot before vblank starts returns it clear and skips it for the frame
+            self.suppress_vblank = true;
+        } else if self.vblank_just_started() {
+            // Reading it as it starts returns it set, but the NMI never reaches the CPU
+            self.nmi_interrupt = None;
+        }
+
         self.status.set_vblank_status(false);
-        self.addr.reset_latch();
-        self.scroll.reset_latch();
+        self.internal.read_status();
         data
     }
 
+    /// Whether vblank started on the current dot or the one before,
+    /// too recently for the CPU to have noticed the NMI
+    fn vblank_just_started(&self) -> bool {
+        self.scanline == 241 && matches!(self.cycles, 1 | 2)
+    }
+
+    pub fn peek_status(&self) -> u8 {
+        self.status.bits()
+    }
+
     pub fn write_to_oam_addr(&mut self, value: u8) {
         *self.oam_addr = value;
     }
@@ -77,116 +115,416 @@ impl PPU {
         self.oam_data[*self.oam_addr as usize]
     }
 
+    pub fn peek_oam_data(&self) -> u8 {
+        self.oam_data[*self.oam_addr as usize]
+    }
+
+    /// $2004 write, incrementing OAMADDR
     pub fn write_to_oam_data(&mut self, value: u8) {
         self.oam_data[*self.oam_addr as usize] = value;
+        *self.oam_addr = self.oam_addr.wrapping_add(1);
     }
 
     pub fn write_to_scroll(&mut self, value: u8) {
-        self.scroll.write(value);
+        self.internal.write_scroll(value);
     }
 
     pub fn write_to_addr(&mut self, value: u8) {
-        self.addr.update(value);
+        self.internal.write_addr(value);
+        self.mapper.borrow_mut().ppu_bus_address(self.vram_addr());
     }
 
     pub fn read_data(&mut self) -> u8 {
-        let addr = self.addr.get();
+        let addr = self.vram_addr();
+        self.mapper.borrow_mut().ppu_bus_address(addr);
         self.increment_vram_addr();
 
         match addr {
             0..=0x1FFF => {
                 let result = self.internal_data_buf;
-                self.internal_data_buf = self.chr_rom[addr as usize];
+                self.internal_data_buf = self.mapper.borrow_mut().ppu_read(addr);
                 result
             }
-            PPUCTRL..=0x2fff => {
+            PPUCTRL..=0x3EFF => {
                 let result = self.internal_data_buf;
-                self.internal_data_buf = self.vram[self.mirror_vram_addr(addr) as usize];
+                self.internal_data_buf = self.read_nametable(addr);
                 result
             }
-            0x3000..=0x3eff => panic!(
-                "addr space 0x3000..0x3eff is not expected to be used, requested = {} ",
-                addr
-            ),
-            0x3f00..=0x3fff => self.palette_table[(addr - 0x3f00) as usize],
+            0x3F00..=0x3FFF => self.palette_table[Self::palette_index(addr)],
             _ => panic!("unexpected access to mirrored space {}", addr),
         }
     }
 
+    /// The value `read_data` would return: the read buffer, or the palette entry
+    pub fn peek_data(&self) -> u8 {
+        match self.vram_addr() {
+            addr @ 0x3F00..=0x3FFF => self.peek_vram(addr),
+            _ => self.internal_data_buf,
+        }
+    }
+
+    /// Read the PPU address space $0000-$3FFF without any side effect, for memory viewers
+    pub fn peek_vram(&self, addr: u16) -> u8 {
+        match addr & 0x3FFF {
+            addr @ 0..=0x1FFF => self.mapper.borrow().ppu_peek(addr),
+            addr @ 0x2000..=0x3EFF => match self.mapper.borrow().nametable(addr) {
+                Nametable::Console(index) => self.vram[index as usize],
+                Nametable::Cartridge => self.mapper.borrow().ppu_peek(addr),
+            },
+            addr => self.palette_table[Self::palette_index(addr)],
+        }
+    }
+
+    /// Index in the palette table of $3F00-$3FFF, mirrored every 32 bytes
+    fn palette_index(addr: u16) -> usize {
+        let index = (addr - 0x3F00) as usize % 32;
+        // Entries 0x10/0x14/0x18/0x1C are mirrors of 0x00/0x04/0x08/0x0C
+        match index {
+            0x10 | 0x14 | 0x18 | 0x1C => index - 0x10,
+            _ => index,
+        }
+    }
+
     pub fn write_data(&mut self, value: u8) {
-        let addr = self.addr.get();
+        let addr = self.vram_addr();
+        self.mapper.borrow_mut().ppu_bus_address(addr);
 
         match addr {
-            0..=0x1FFF => {
-                panic!("Attempted to write to chr_rom space")
-            }
-            PPUCTRL..=0x2FFF => {
-                self.vram[self.mirror_vram_addr(addr) as usize] = value;
-            }
-            0x3000..=0x3EFF => panic!(
-                "addr space 0x3000..0x3eff is not expected to be used, requested = {} ",
-                addr
-            ),
-            // Addresses 0x3F10/0x3F14/0x3F18/0x3F1C are mirrors of 0x3F00/0x3F04/0x3F08/0x3F0C
-            0x3F10 | 0x3F14 | 0x3F18 | 0x3F1C => {
-                self.palette_table[(addr - 0x3F10) as usize] = value;
-            }
-            0x3F00..=0x3FFF => {
-                self.palette_table[(addr - 0x3F00) as usize] = value;
-            }
+            0..=0x1FFF => self.mapper.borrow_mut().ppu_write(addr, value),
+            PPUCTRL..=0x3EFF => self.write_nametable(addr, value),
+            0x3F00..=0x3FFF => self.palette_table[Self::palette_index(addr)] = value,
             _ => panic!("unexpected access to mirrored space {}", addr),
         }
+
+        self.increment_vram_addr();
+    }
+
+    /// The address $2007 accesses, `v` without its fine Y bit
+    fn vram_addr(&self) -> u16 {
+        self.internal.v.get() & 0x3FFF
     }
 
     fn increment_vram_addr(&mut self) {
-        self.addr.increment(self.ctrl.vram_addr_increment());
+        if self.is_rendering() {
+            // While rendering, $2007 accesses trigger a coarse X and a Y increment at the same time
+            self.internal.v.increment_x();
+            self.internal.v.increment_y();
+        } else {
+            self.internal.increment(self.ctrl.vram_addr_increment());
+        }
+    }
+
+    /// Whether the rendering pipeline is driving `v`: on visible and pre-render lines
+    /// with the background or sprites enabled
+    fn is_rendering(&self) -> bool {
+        self.mask.is_rendering_enabled() && (self.scanline < 240 || self.scanline == 261)
     }
 
-    // Horizontal:
-    //   [ A ] [ a ]
-    //   [ B ] [ b ]
-    // Vertical:
-    //   [ A ] [ B ]
-    //   [ a ] [ b ]
-    fn mirror_vram_addr(&self, addr: u16) -> u16 {
-        // Mirror down 0x3000-0x3EFF to 0x2000 - 0x2EFF
-        let mirrored_vram = addr & 0b11111111111111;
-        // To VRAM vector
-        let vram_index = mirrored_vram - PPUCTRL;
-        // To the name table index
-        let name_table = vram_index / 0x400;
-        match (self.mirroring, name_table) {
-            (Mirroring::Horizontal, 1 | 2) => vram_index - 0x400,
-            (Mirroring::Vertical, 2 | 3) | (Mirroring::Horizontal, 3) => vram_index - 0x800,
-            _ => vram_index,
+    /// Read a nametable byte from wherever the cartridge maps `addr`
+    fn read_nametable(&mut self, addr: u16) -> u8 {
+        let nametable = self.mapper.borrow().nametable(addr);
+        match nametable {
+            Nametable::Console(index) => self.vram[index as usize],
+            Nametable::Cartridge => self.mapper.borrow_mut().ppu_read(addr),
         }
     }
 
-    pub fn tick(&mut self, cycles: u8) -> bool {
-        self.cycles += cycles as usize;
+    fn write_nametable(&mut self, addr: u16, value: u8) {
+        let nametable = self.mapper.borrow().nametable(addr);
+        match nametable {
+            Nametable::Console(index) => self.vram[index as usize] = value,
+            Nametable::Cartridge => self.mapper.borrow_mut().ppu_write(addr, value),
+        }
+    }
 
-        if self.cycles < 341 {
-            return false;
+    pub fn tick(&mut self, cycles: u8) -> bool {
+        let mut frame_complete = false;
+        for _ in 0..cycles {
+            frame_complete |= self.tick_dot();
         }
+        frame_complete
+    }
+
+    /// Advance one dot, returns whether the frame is complete
+    fn tick_dot(&mut self) -> bool {
+        self.cycles += 1;
+
+        // Odd frames skip the last dot of the pre-render line when rendering is enabled
+        let skip = self.scanline == 261
+            && self.cycles == 340
+            && self.odd_frame
+            && self.mask.is_rendering_enabled();
 
-        self.cycles -= 341;
-        self.scanline += 1;
+        let mut frame_complete = false;
+        if self.cycles == 341 || skip {
+            self.cycles = 0;
+            self.scanline += 1;
 
-        if self.scanline == 241 && self.ctrl.generate_vblank_nmi() {
-            self.status.set_vblank_status(true);
-            self.nmi_interrupt = Some(());
+            if self.scanline == 262 {
+                self.scanline = 0;
+                self.odd_frame = !self.odd_frame;
+                frame_complete = true;
+            }
         }
 
-        if self.scanline >= 262 {
-            self.scanline = 0;
-            self.status.set_vblank_status(false);
-            true
-        } else {
-            false
+        match (self.scanline, self.cycles) {
+            (241, 1) if !self.suppress_vblank => {
+                self.status.set_vblank_status(true);
+                if self.ctrl.generate_vblank_nmi() {
+                    self.nmi_interrupt = Some(());
+                }
+            }
+            (241, 1) => self.suppress_vblank = false,
+            (261, 1) => {
+                self.status.remove(
+                    StatusRegister::VBLANK_STARTED
+                        | StatusRegister::SPRITE_ZERO_HIT
+                        | StatusRegister::SPRITE_OVERFLOW,
+                );
+            }
+            _ => {}
         }
+
+        self.render_dot();
+
+        frame_complete
     }
 
     pub fn poll_nmi_interrupt(&mut self) -> Option<()> {
         self.nmi_interrupt.take()
     }
+
+    /// Whether an NMI is waiting to be polled, without acknowledging it
+    pub fn nmi_pending(&self) -> bool {
+        self.nmi_interrupt.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::{new_mapper, tests::test_rom, Mirroring};
+
+    use super::*;
+
+    #[test]
+    fn data_increments_address() {
+        let mut ppu = PPU::new(new_mapper(test_rom(&[])).unwrap());
+
+        ppu.write_to_addr(0x20);
+        ppu.write_to_addr(0x00);
+        ppu.write_data(1);
+        ppu.write_data(2);
+        ppu.write_to_ctrl(ControlRegister::VRAM_ADDRESS_INCREMENT.bits());
+        ppu.write_data(3);
+
+        assert_eq!(ppu.vram[..3], [1, 2, 3]);
+        assert_eq!(ppu.internal.v.get(), 0x2022);
+    }
+
+    #[test]
+    fn data_increments_while_rendering() {
+        let mut ppu = PPU::new(new_mapper(test_rom(&[])).unwrap());
+        ppu.write_to_addr(0x20);
+        ppu.write_to_addr(0x00);
+        ppu.write_to_mask(MaskRegister::SHOW_BACKGROUND.bits());
+
+        // $2000 sets fine Y to 2
+        ppu.write_data(0);
+        assert_eq!(ppu.internal.v.coarse_x(), 1);
+        assert_eq!(ppu.internal.v.fine_y(), 3);
+    }
+
+    #[test]
+    fn peek_palette_matches_read() {
+        let mut ppu = PPU::new(new_mapper(test_rom(&[])).unwrap());
+        ppu.write_to_addr(0x3F);
+        ppu.write_to_addr(0x10);
+        ppu.write_data(0x2A);
+
+        for addr in [0x3F00, 0x3F10] {
+            ppu.write_to_addr((addr >> 8) as u8);
+            ppu.write_to_addr(addr as u8);
+            let peeked = ppu.peek_data();
+            assert_eq!(ppu.read_data(), peeked);
+            assert_eq!(peeked, 0x2A);
+        }
+    }
+
+    #[test]
+    fn palette_mirrors() {
+        let mut ppu = PPU::new(new_mapper(test_rom(&[])).unwrap());
+        ppu.write_to_addr(0x3F);
+        ppu.write_to_addr(0x10);
+        ppu.write_data(0x11);
+        ppu.write_to_addr(0x3F);
+        ppu.write_to_addr(0xE5);
+        ppu.write_data(0x22);
+
+        // $3F20-$3FFF mirrors $3F00-$3F1F, and $3F10 mirrors $3F00
+        ppu.write_to_addr(0x3F);
+        ppu.write_to_addr(0x10);
+        assert_eq!(ppu.read_data(), 0x11);
+        assert_eq!(ppu.palette_table[0x00], 0x11);
+        assert_eq!(ppu.palette_table[0x05], 0x22);
+        ppu.write_to_addr(0x3F);
+        ppu.write_to_addr(0x25);
+        assert_eq!(ppu.read_data(), 0x22);
+        ppu.write_to_addr(0x3F);
+        ppu.write_to_addr(0xF0);
+        assert_eq!(ppu.read_data(), 0x11);
+    }
+
+    #[test]
+    fn nametable_mirror() {
+        let mut ppu = PPU::new(new_mapper(test_rom(&[])).unwrap());
+        for (addr, value) in [0x2000u16, 0x2455, 0x2AAA, 0x2EFE].into_iter().zip(1..) {
+            ppu.write_to_addr((addr >> 8) as u8);
+            ppu.write_to_addr(addr as u8);
+            ppu.write_data(value);
+        }
+
+        // $3000-$3EFF reads and writes land in $2000-$2EFF
+        for (addr, value) in [0x3000u16, 0x3455, 0x3AAA, 0x3EFE].into_iter().zip(1..) {
+            ppu.write_to_addr((addr >> 8) as u8);
+            ppu.write_to_addr(addr as u8);
+            ppu.read_data();
+            assert_eq!(ppu.read_data(), value, "{addr:04X}");
+            assert_eq!(ppu.peek_vram(addr), ppu.peek_vram(addr - 0x1000));
+        }
+
+        ppu.write_to_addr(0x31);
+        ppu.write_to_addr(0x23);
+        ppu.write_data(0x42);
+        assert_eq!(ppu.peek_vram(0x2123), 0x42);
+    }
+
+    #[test]
+    fn four_screen() {
+        let mut rom = test_rom(&[]);
+        rom.screen_mirroring = Mirroring::FourScreen;
+        let mut ppu = PPU::new(new_mapper(rom).unwrap());
+
+        for (nametable, value) in [0x20, 0x24, 0x28, 0x2C].into_iter().zip(1..) {
+            ppu.write_to_addr(nametable);
+            ppu.write_to_addr(0x00);
+            ppu.write_data(value);
+        }
+
+        assert_eq!(ppu.vram[0x000], 1);
+        assert_eq!(ppu.vram[0x400], 2);
+        assert_eq!(ppu.peek_vram(0x2800), 3);
+        assert_eq!(ppu.peek_vram(0x2C00), 4);
+        // $3000-$3EFF mirrors the nametables
+        assert_eq!(ppu.peek_vram(0x3C00), 4);
+    }
+
+    #[test]
+    fn chr_ram() {
+        let mut rom = test_rom(&[]);
+        rom.chr_rom.clear();
+        rom.chr_ram_size = 0x2000;
+        let mut ppu = PPU::new(new_mapper(rom).unwrap());
+
+        ppu.write_to_addr(0x10);
+        ppu.write_to_addr(0x00);
+        ppu.write_data(0x55);
+
+        ppu.write_to_addr(0x10);
+        ppu.write_to_addr(0x00);
+        ppu.read_data();
+        assert_eq!(ppu.read_data(), 0x55);
+    }
+
+    fn tick_to(ppu: &mut PPU, scanline: u16, dot: usize) {
+        while (ppu.scanline, ppu.cycles) != (scanline, dot) {
+            ppu.tick(1);
+        }
+    }
+
+    fn nmi_ppu() -> PPU {
+        let mut ppu = PPU::new(new_mapper(test_rom(&[])).unwrap());
+        ppu.write_to_ctrl(ControlRegister::GENERATE_NMI.bits());
+        ppu
+    }
+
+    #[test]
+    fn vblank() {
+        let mut ppu = nmi_ppu();
+
+        tick_to(&mut ppu, 241, 0);
+        assert!(!ppu.status.is_in_vblank());
+        assert!(!ppu.nmi_pending());
+
+        tick_to(&mut ppu, 241, 1);
+        assert!(ppu.status.is_in_vblank());
+        assert!(ppu.nmi_pending());
+
+        tick_to(&mut ppu, 261, 0);
+        assert!(ppu.status.is_in_vblank());
+        tick_to(&mut ppu, 261, 1);
+        assert!(!ppu.status.is_in_vblank());
+    }
+
+    #[test_case(0, false, false ; "one_dot_before")]
+    #[test_case(1, true, false ; "same_dot")]
+    #[test_case(2, true, false ; "one_dot_after")]
+    #[test_case(3, true, true ; "two_dots_after")]
+    fn read_status_near_vblank(dot: usize, vblank: bool, nmi: bool) {
+        let mut ppu = nmi_ppu();
+        tick_to(&mut ppu, 241, dot);
+
+        let status = ppu.read_status();
+        assert_eq!(status & StatusRegister::VBLANK_STARTED.bits() != 0, vblank);
+
+        tick_to(&mut ppu, 241, 10);
+        assert!(!ppu.status.is_in_vblank());
+        assert_eq!(ppu.nmi_pending(), nmi);
+    }
+
+    #[test]
+    fn suppressed_vblank_comes_back_next_frame() {
+        let mut ppu = nmi_ppu();
+        tick_to(&mut ppu, 241, 0);
+        ppu.read_status();
+
+        tick_to(&mut ppu, 240, 0);
+        tick_to(&mut ppu, 241, 1);
+        assert!(ppu.status.is_in_vblank());
+        assert!(ppu.nmi_pending());
+    }
+
+    #[test]
+    fn nmi_refires_when_enabled_during_vblank() {
+        let mut ppu = nmi_ppu();
+        tick_to(&mut ppu, 241, 1);
+        assert!(ppu.poll_nmi_interrupt().is_some());
+
+        ppu.write_to_ctrl(ControlRegister::GENERATE_NMI.bits());
+        assert!(!ppu.nmi_pending());
+
+        for _ in 0..2 {
+            ppu.write_to_ctrl(0);
+            ppu.write_to_ctrl(ControlRegister::GENERATE_NMI.bits());
+            assert!(ppu.poll_nmi_interrupt().is_some());
+        }
+
+        ppu.read_status();
+        ppu.write_to_ctrl(0);
+        ppu.write_to_ctrl(ControlRegister::GENERATE_NMI.bits());
+        assert!(!ppu.nmi_pending());
+    }
+
+    #[test_case(1, false ; "same_dot")]
+    #[test_case(2, false ; "one_dot_after")]
+    #[test_case(3, true ; "two_dots_after")]
+    fn disable_nmi_near_vblank(dot: usize, nmi: bool) {
+        let mut ppu = nmi_ppu();
+        tick_to(&mut ppu, 241, dot);
+
+        ppu.write_to_ctrl(0);
+        assert_eq!(ppu.nmi_pending(), nmi);
+        assert!(ppu.status.is_in_vblank());
+    }
 }
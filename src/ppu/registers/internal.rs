@@ -0,0 +1,316 @@
+pub const PPUSCROLL: u16 = 0x2005;
+pub const PPUADDR: u16 = 0x2006;
+
+/// 15-bit VRAM address, doubling as the scroll position while rendering
+///
+/// yyy NN YYYYY XXXXX
+/// ||| || ||||| +++++-- coarse X scroll
+/// ||| || +++++-------- coarse Y scroll
+/// ||| ++-------------- nametable select
+/// +++----------------- fine Y scroll
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VramAddress(u16);
+
+impl VramAddress {
+    const COARSE_X: u16 = 0x001F;
+    const COARSE_Y: u16 = 0x03E0;
+    const NAMETABLE: u16 = 0x0C00;
+    const NAMETABLE_X: u16 = 0x0400;
+    const NAMETABLE_Y: u16 = 0x0800;
+    const FINE_Y: u16 = 0x7000;
+    const HORIZONTAL: u16 = Self::NAMETABLE_X | Self::COARSE_X;
+    const VERTICAL: u16 = Self::FINE_Y | Self::NAMETABLE_Y | Self::COARSE_Y;
+
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn get(&self) -> u16 {
+        self.0
+    }
+
+    pub fn set(&mut self, value: u16) {
+        self.0 = value & 0x7FFF;
+    }
+
+    pub fn coarse_x(&self) -> u8 {
+        (self.0 & Self::COARSE_X) as u8
+    }
+
+    pub fn coarse_y(&self) -> u8 {
+        ((self.0 & Self::COARSE_Y) >> 5) as u8
+    }
+
+    /// Nametable select, 0 to 3 for $2000, $2400, $2800 and $2C00
+    pub fn nametable(&self) -> u8 {
+        ((self.0 & Self::NAMETABLE) >> 10) as u8
+    }
+
+    pub fn fine_y(&self) -> u8 {
+        ((self.0 & Self::FINE_Y) >> 12) as u8
+    }
+
+    /// Address of the nametable byte of the current tile
+    pub fn tile_address(&self) -> u16 {
+        0x2000 | (self.0 & 0x0FFF)
+    }
+
+    /// Address of the attribute byte of the current tile
+    pub fn attribute_address(&self) -> u16 {
+        0x23C0 | (self.0 & Self::NAMETABLE) | ((self.0 >> 4) & 0x38) | ((self.0 >> 2) & 0x07)
+    }
+
+    /// Move to the next tile, wrapping into the horizontally adjacent nametable
+    pub fn increment_x(&mut self) {
+        if self.coarse_x() == 31 {
+            self.0 &= !Self::COARSE_X;
+            self.0 ^= Self::NAMETABLE_X;
+        } else {
+            self.0 += 1;
+        }
+    }
+
+    /// Move to the next pixel row, wrapping into the vertically adjacent nametable after row 29.
+    /// Coarse Y set out of bounds (30 or 31) wraps to 0 without switching nametables
+    pub fn increment_y(&mut self) {
+        if self.fine_y() < 7 {
+            self.0 += 0x1000;
+            return;
+        }
+
+        self.0 &= !Self::FINE_Y;
+        let coarse_y = match self.coarse_y() {
+            29 => {
+                self.0 ^= Self::NAMETABLE_Y;
+                0
+            }
+            31 => 0,
+            coarse_y => coarse_y + 1,
+        };
+        self.0 = (self.0 & !Self::COARSE_Y) | ((coarse_y as u16) << 5);
+    }
+
+    /// Copy coarse X and the horizontal nametable bit from `other`
+    pub fn copy_horizontal(&mut self, other: VramAddress) {
+        self.0 = (self.0 & !Self::HORIZONTAL) | (other.0 & Self::HORIZONTAL);
+    }
+
+    /// Copy fine Y, coarse Y and the vertical nametable bit from `other`
+    pub fn copy_vertical(&mut self, other: VramAddress) {
+        self.0 = (self.0 & !Self::VERTICAL) | (other.0 & Self::VERTICAL);
+    }
+}
+
+/// Internal registers shared by PPUCTRL, PPUSCROLL, PPUADDR and PPUSTATUS,
+/// named after the loopy document that first described them.
+///
+/// $2005 and $2006 share the write toggle `w` and both write into `t`,
+/// which is copied into `v` by the second $2006 write and by the rendering pipeline.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct InternalRegisters {
+    /// Current VRAM address
+    pub v: VramAddress,
+    /// Temporary VRAM address, the top left onscreen tile
+    pub t: VramAddress,
+    /// Fine X scroll (3 bits)
+    pub x: u8,
+    /// First or second write toggle
+    pub w: bool,
+}
+
+impl InternalRegisters {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// $2000 write: t: ...GH.. ........ <- d: ......GH
+    pub fn write_ctrl(&mut self, value: u8) {
+        let t = self.t.get() & !VramAddress::NAMETABLE;
+        self.t.set(t | ((value as u16 & 0b11) << 10));
+    }
+
+    /// $2002 read: w <- 0
+    pub fn read_status(&mut self) {
+        self.w = false;
+    }
+
+    /// $2005 first write: t: ....... ...ABCDE <- d: ABCDE..., x <- d: .....FGH
+    /// $2005 second write: t: FGH..AB CDE..... <- d: ABCDEFGH
+    pub fn write_scroll(&mut self, value: u8) {
+        let value = value as u16;
+        let t = self.t.get();
+
+        if self.w {
+            let t = t & !(VramAddress::FINE_Y | VramAddress::COARSE_Y);
+            self.t
+                .set(t | ((value & 0b111) << 12) | ((value >> 3) << 5));
+        } else {
+            self.t.set((t & !VramAddress::COARSE_X) | (value >> 3));
+            self.x = value as u8 & 0b111;
+        }
+
+        self.w = !self.w;
+    }
+
+    /// $2006 first write: t: .CDEFGH ........ <- d: ..CDEFGH, bit 14 of t cleared
+    /// $2006 second write: t: ....... ABCDEFGH <- d: ABCDEFGH, v <- t
+    pub fn write_addr(&mut self, value: u8) {
+        let value = value as u16;
+        let t = self.t.get();
+
+        if self.w {
+            self.t.set((t & 0xFF00) | value);
+            self.v = self.t;
+        } else {
+            self.t.set((t & 0x00FF) | ((value & 0x3F) << 8));
+        }
+
+        self.w = !self.w;
+    }
+
+    /// $2007 access outside of rendering: v is incremented by 1 or 32
+    pub fn increment(&mut self, inc: u8) {
+        self.v.set(self.v.get().wrapping_add(inc as u16));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_addr() {
+        let mut registers = InternalRegisters::new();
+
+        registers.write_addr(0x03);
+        assert_eq!(registers.v.get(), 0);
+        registers.write_addr(0x21);
+
+        assert_eq!(registers.v.get(), 0x0321);
+        assert!(!registers.w);
+    }
+
+    #[test]
+    fn write_addr_clears_bit_14() {
+        let mut registers = InternalRegisters::new();
+
+        registers.write_addr(0xFF);
+        registers.write_addr(0x00);
+
+        assert_eq!(registers.v.get(), 0x3F00);
+    }
+
+    #[test]
+    fn write_scroll() {
+        let mut registers = InternalRegisters::new();
+
+        registers.write_ctrl(0b10);
+        registers.write_scroll(0b0111_1101);
+        registers.write_scroll(0b1010_1011);
+
+        assert_eq!(registers.t.coarse_x(), 0b01111);
+        assert_eq!(registers.x, 0b101);
+        assert_eq!(registers.t.coarse_y(), 0b10101);
+        assert_eq!(registers.t.fine_y(), 0b011);
+        assert_eq!(registers.t.nametable(), 0b10);
+        assert_eq!(registers.v.get(), 0);
+    }
+
+    #[test]
+    fn shared_toggle() {
+        let mut registers = InternalRegisters::new();
+
+        registers.write_scroll(0);
+        assert!(registers.w);
+        registers.write_addr(0x3F);
+        assert!(!registers.w);
+
+        registers.write_scroll(0);
+        registers.read_status();
+        assert!(!registers.w);
+    }
+
+    #[test]
+    fn split_scroll() {
+        // $2006/$2005/$2005/$2006 sets every bit of v mid frame
+        let mut registers = InternalRegisters::new();
+
+        registers.write_addr(0b0000_0100);
+        registers.write_scroll(0b0101_0000);
+        registers.write_scroll(0b0000_1000);
+        registers.write_addr(0b0100_0001);
+
+        assert_eq!(registers.v.nametable(), 1);
+        assert_eq!(registers.v.coarse_y(), 0b01010);
+        assert_eq!(registers.v.coarse_x(), 1);
+        assert_eq!(registers.x, 0);
+    }
+
+    #[test]
+    fn increment() {
+        let mut registers = InternalRegisters::new();
+
+        registers.write_addr(0x01);
+        registers.write_addr(0x22);
+        registers.increment(1);
+        assert_eq!(registers.v.get(), 0x0123);
+
+        registers.increment(32);
+        assert_eq!(registers.v.get(), 0x0143);
+    }
+
+    #[test]
+    fn increment_x() {
+        let mut address = VramAddress::new();
+        address.set(0x001E);
+
+        address.increment_x();
+        assert_eq!(address.coarse_x(), 31);
+        address.increment_x();
+        assert_eq!(address.coarse_x(), 0);
+        assert_eq!(address.nametable(), 1);
+    }
+
+    #[test]
+    fn increment_y() {
+        let mut address = VramAddress::new();
+        address.set(0x63A0);
+
+        address.increment_y();
+        assert_eq!((address.fine_y(), address.coarse_y()), (7, 29));
+        address.increment_y();
+        assert_eq!((address.fine_y(), address.coarse_y()), (0, 0));
+        assert_eq!(address.nametable(), 0b10);
+    }
+
+    #[test]
+    fn increment_y_out_of_bounds() {
+        let mut address = VramAddress::new();
+        address.set(0x73E0);
+
+        address.increment_y();
+        assert_eq!((address.fine_y(), address.coarse_y()), (0, 0));
+        assert_eq!(address.nametable(), 0);
+    }
+
+    #[test]
+    fn copy() {
+        let mut v = VramAddress::new();
+        let mut t = VramAddress::new();
+        t.set(0x7FFF);
+
+        v.copy_horizontal(t);
+        assert_eq!(v.get(), 0x041F);
+        v.copy_vertical(t);
+        assert_eq!(v.get(), 0x7FFF);
+    }
+
+    #[test]
+    fn attribute_address() {
+        let mut address = VramAddress::new();
+        address.set(0x0EAF);
+
+        assert_eq!(address.tile_address(), 0x2EAF);
+        assert_eq!(address.attribute_address(), 0x2FEB);
+    }
+}
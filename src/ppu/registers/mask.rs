@@ -37,6 +37,10 @@ impl MaskRegister {
         Default::default()
     }
 
+    pub fn is_rendering_enabled(&self) -> bool {
+        self.intersects(Self::SHOW_BACKGROUND | Self::SHOW_SPRITES)
+    }
+
     pub fn update(&mut self, value: u8) {
         *self = Self::from_bits_truncate(value);
     }
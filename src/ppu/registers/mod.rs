@@ -1,21 +1,19 @@
-pub mod address;
 pub mod control;
 pub mod data;
+pub mod internal;
 pub mod mask;
 pub mod oam_address;
 pub mod oam_data;
 pub mod oam_dma;
-pub mod scroll;
 pub mod status;
 
-pub use address::*;
 pub use control::*;
 pub use data::*;
+pub use internal::*;
 pub use mask::*;
 pub use oam_address::*;
 pub use oam_data::*;
 pub use oam_dma::*;
-pub use scroll::*;
 pub use status::*;
 
 use core::ops::{Deref, DerefMut};
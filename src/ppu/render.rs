@@ -0,0 +1,774 @@
+use super::{registers::*, PPU, SYSTEM_PALETTE};
+
+/// Sprites the PPU can draw on a single scanline
+pub const SPRITES_PER_SCANLINE: usize = 8;
+
+bitflags::bitflags! {
+    /// Byte 2 of an OAM entry
+    ///
+    /// 76543210
+    /// ||||||||
+    /// ||||||++- Palette (4 to 7) of sprite
+    /// |||+++--- Unimplemented (read 0)
+    /// ||+------ Priority (0: in front of background; 1: behind background)
+    /// |+------- Flip sprite horizontally
+    /// +-------- Flip sprite vertically
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct SpriteAttributes: u8 {
+        const PALETTE1          = 0b00000001;
+        const PALETTE2          = 0b00000010;
+        const BEHIND_BACKGROUND = 0b00100000;
+        const FLIP_HORIZONTALLY = 0b01000000;
+        const FLIP_VERTICALLY   = 0b10000000;
+    }
+}
+
+impl SpriteAttributes {
+    pub fn palette(&self) -> u8 {
+        self.bits() & 0b11
+    }
+}
+
+/// Sprite loaded for the current scanline by the fetches at dots 257-320
+#[derive(Debug, Default, Clone, Copy)]
+struct ScanlineSprite {
+    x: u8,
+    attributes: SpriteAttributes,
+    /// Whether this is the first entry of OAM, the one triggering sprite 0 hits
+    zero: bool,
+    /// Pattern bit planes, already flipped horizontally if needed
+    lo: u8,
+    hi: u8,
+}
+
+impl ScanlineSprite {
+    /// Color (1-3) of the sprite at screen column `x`, 0 when transparent or not covering `x`
+    fn pixel(&self, x: usize) -> u8 {
+        let Some(column) = x.checked_sub(self.x as usize).filter(|column| *column < 8) else {
+            return 0;
+        };
+        let shift = 7 - column;
+        ((self.hi >> shift) & 1) << 1 | ((self.lo >> shift) & 1)
+    }
+}
+
+/// Latches, shift registers and sprite evaluation state of the rendering pipeline
+#[derive(Debug, Default)]
+pub(super) struct Pipeline {
+    // Background tile being fetched
+    nametable: u8,
+    attribute: u8,
+    pattern_lo: u8,
+    pattern_hi: u8,
+
+    // Background shift registers, the high byte is the tile being drawn
+    shift_pattern_lo: u16,
+    shift_pattern_hi: u16,
+    shift_attribute_lo: u16,
+    shift_attribute_hi: u16,
+
+    // Sprite evaluation into secondary OAM
+    secondary_oam: [u8; 32],
+    oam_latch: u8,
+    /// Sprite index in OAM
+    n: usize,
+    /// Byte index in the sprite
+    m: usize,
+    found: usize,
+    evaluation_done: bool,
+    sprite_zero_found: bool,
+
+    sprites: [ScanlineSprite; SPRITES_PER_SCANLINE],
+}
+
+impl PPU {
+    /// Work done on the current dot: background and sprite fetches, evaluation
+    /// of the sprites for the next line and output of one pixel on visible lines
+    pub(super) fn render_dot(&mut self) {
+        let dot = self.cycles;
+        let visible = self.scanline < 240;
+        let rendering = self.is_rendering();
+
+        if rendering && matches!(dot, 2..=257 | 322..=337) {
+            self.shift_background();
+        }
+
+        if visible && (1..=256).contains(&dot) {
+            self.output_pixel(dot - 1);
+        }
+
+        if rendering {
+            self.background_dot(dot);
+            self.sprite_dot(dot, visible);
+        }
+    }
+
+    fn background_dot(&mut self, dot: usize) {
+        if matches!(dot, 9..=257 | 329 | 337) && dot % 8 == 1 {
+            self.reload_background();
+        }
+
+        if matches!(dot, 1..=256 | 321..=336) {
+            let v = self.internal.v;
+            match dot % 8 {
+                2 => self.pipeline.nametable = self.fetch(v.tile_address()),
+                4 => {
+                    // Every attribute byte covers 4x4 tiles, 2 bits for each 2x2 quadrant
+                    let shift = ((v.coarse_y() & 0b10) << 1) | (v.coarse_x() & 0b10);
+                    self.pipeline.attribute = (self.fetch(v.attribute_address()) >> shift) & 0b11;
+                }
+                6 => self.pipeline.pattern_lo = self.fetch(self.background_pattern_address()),
+                0 => {
+                    let addr = self.background_pattern_address() + 8;
+                    self.pipeline.pattern_hi = self.fetch(addr);
+                    self.internal.v.increment_x();
+                }
+                _ => {}
+            }
+        }
+
+        match dot {
+            256 => self.internal.v.increment_y(),
+            257 => self.internal.v.copy_horizontal(self.internal.t),
+            280..=304 if self.scanline == 261 => self.internal.v.copy_vertical(self.internal.t),
+            // Unused nametable fetches at the end of the line
+            338 | 340 => {
+                self.fetch(self.internal.v.tile_address());
+            }
+            _ => {}
+        }
+    }
+
+    fn background_pattern_address(&self) -> u16 {
+        self.ctrl.background_pattern_table_address()
+            + self.pipeline.nametable as u16 * 16
+            + self.internal.v.fine_y() as u16
+    }
+
+    fn shift_background(&mut self) {
+        let pipeline = &mut self.pipeline;
+        pipeline.shift_pattern_lo <<= 1;
+        pipeline.shift_pattern_hi <<= 1;
+        pipeline.shift_attribute_lo <<= 1;
+        pipeline.shift_attribute_hi <<= 1;
+    }
+
+    /// Load the fetched tile into the low byte of the shift registers
+    fn reload_background(&mut self) {
+        let pipeline = &mut self.pipeline;
+        let expand = |bit: u8| if bit != 0 { 0xFF } else { 0x00 };
+
+        pipeline.shift_pattern_lo =
+            (pipeline.shift_pattern_lo & 0xFF00) | pipeline.pattern_lo as u16;
+        pipeline.shift_pattern_hi =
+            (pipeline.shift_pattern_hi & 0xFF00) | pipeline.pattern_hi as u16;
+        pipeline.shift_attribute_lo =
+            (pipeline.shift_attribute_lo & 0xFF00) | expand(pipeline.attribute & 0b01);
+        pipeline.shift_attribute_hi =
+            (pipeline.shift_attribute_hi & 0xFF00) | expand(pipeline.attribute & 0b10);
+    }
+
+    fn sprite_dot(&mut self, dot: usize, visible: bool) {
+        match dot {
+            // Secondary OAM is cleared during dots 1-64
+            1..=64 if dot.is_multiple_of(2) => self.pipeline.secondary_oam[dot / 2 - 1] = 0xFF,
+            // The pre-render line does not evaluate sprites, so none are drawn on line 0
+            65..=256 if visible => self.evaluate_sprites(dot),
+            65 => self.pipeline.found = 0,
+            257..=320 => {
+                *self.oam_addr = 0;
+                self.fetch_sprite(dot - 257);
+            }
+            _ => {}
+        }
+    }
+
+    /// One dot of sprite evaluation for the next line: OAM is read on odd dots
+    /// and secondary OAM written on even ones.
+    ///
+    /// Once eight sprites are found the PPU keeps scanning OAM for a ninth, but a hardware bug
+    /// increments the byte index along with the sprite index, so it compares tile, attribute
+    /// and X bytes against the scanline as if they were Y coordinates.
+    fn evaluate_sprites(&mut self, dot: usize) {
+        let height = self.ctrl.sprite_size() as usize;
+        let scanline = self.scanline as usize;
+        let pipeline = &mut self.pipeline;
+
+        if dot == 65 {
+            pipeline.n = 0;
+            pipeline.m = 0;
+            pipeline.found = 0;
+            pipeline.evaluation_done = false;
+            pipeline.sprite_zero_found = false;
+        }
+
+        if pipeline.evaluation_done {
+            return;
+        }
+
+        if dot % 2 == 1 {
+            pipeline.oam_latch = self.oam_data[pipeline.n * 4 + pipeline.m];
+            return;
+        }
+
+        let in_range = scanline
+            .checked_sub(pipeline.oam_latch as usize)
+            .is_some_and(|row| row < height);
+
+        if pipeline.found < SPRITES_PER_SCANLINE {
+            pipeline.secondary_oam[pipeline.found * 4 + pipeline.m] = pipeline.oam_latch;
+            if pipeline.m == 0 && !in_range {
+                pipeline.n += 1;
+            } else {
+                if pipeline.m == 0 && pipeline.n == 0 {
+                    pipeline.sprite_zero_found = true;
+                }
+                pipeline.m += 1;
+                if pipeline.m == 4 {
+                    pipeline.m = 0;
+                    pipeline.found += 1;
+                    pipeline.n += 1;
+                }
+            }
+        } else if in_range {
+            self.status.insert(StatusRegister::SPRITE_OVERFLOW);
+            pipeline.evaluation_done = true;
+        } else {
+            pipeline.n += 1;
+            pipeline.m = (pipeline.m + 1) % 4;
+        }
+
+        if pipeline.n == 64 {
+            pipeline.evaluation_done = true;
+        }
+    }
+
+    /// Dots 257-320 fetch the pattern of each of the 8 sprite slots in 8 dots,
+    /// unused slots fetching tile $FF
+    fn fetch_sprite(&mut self, offset: usize) {
+        let slot = offset / 8;
+        let entry = &self.pipeline.secondary_oam[slot * 4..slot * 4 + 4];
+        let (y, tile, attributes, x) = (entry[0], entry[1] as u16, entry[2], entry[3]);
+        let attributes = SpriteAttributes::from_bits_retain(attributes);
+        let used = slot < self.pipeline.found;
+
+        let height = self.ctrl.sprite_size() as usize;
+        let row = (self.scanline as usize).wrapping_sub(y as usize) % height;
+        let row = if attributes.contains(SpriteAttributes::FLIP_VERTICALLY) {
+            height - 1 - row
+        } else {
+            row
+        } as u16;
+
+        let addr = if height == 16 {
+            // Bit 0 selects the pattern table, the bottom half is the next tile
+            let table = (tile & 1) * 0x1000;
+            table + ((tile & !1) + row / 8) * 16 + row % 8
+        } else {
+            self.ctrl.sprite_pattern_table_address() + tile * 16 + row
+        };
+
+        match offset % 8 {
+            // Unused nametable and attribute fetches
+            0 | 2 => {
+                self.fetch(self.internal.v.tile_address());
+            }
+            4 => self.pipeline.sprites[slot].lo = self.fetch(addr),
+            6 => {
+                let hi = self.fetch(addr + 8);
+                let sprite = &mut self.pipeline.sprites[slot];
+                sprite.hi = hi;
+                sprite.x = x;
+                sprite.attributes = attributes;
+                sprite.zero = slot == 0 && self.pipeline.sprite_zero_found;
+
+                if !used {
+                    sprite.lo = 0;
+                    sprite.hi = 0;
+                } else if attributes.contains(SpriteAttributes::FLIP_HORIZONTALLY) {
+                    sprite.lo = sprite.lo.reverse_bits();
+                    sprite.hi = sprite.hi.reverse_bits();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Read the PPU bus on behalf of the rendering pipeline, in view of the cartridge
+    fn fetch(&mut self, addr: u16) -> u8 {
+        self.mapper.borrow_mut().ppu_bus_address(addr);
+        if addr < 0x2000 {
+            self.mapper.borrow_mut().ppu_read(addr)
+        } else {
+            self.read_nametable(addr)
+        }
+    }
+
+    /// Draw the pixel at column `x` of the current scanline, output on dot `x` + 1
+    fn output_pixel(&mut self, x: usize) {
+        let background = self.background_pixel(x);
+        let sprite = self.sprite_pixel(x);
+
+        if let Some((_, sprite)) = sprite {
+            // Both pixels are only opaque when both layers are shown, including the left clip.
+            // The hit never happens on the last column
+            if sprite.zero && background != 0 && x != 255 {
+                self.status.insert(StatusRegister::SPRITE_ZERO_HIT);
+            }
+        }
+
+        let color = match sprite {
+            Some((pixel, sprite))
+                if background == 0
+                    || !sprite
+                        .attributes
+                        .contains(SpriteAttributes::BEHIND_BACKGROUND) =>
+            {
+                pixel
+            }
+            _ => background,
+        };
+        let color = self.palette_color(color);
+        self.frame.set_pixel(x, self.scanline as usize, color);
+    }
+
+    /// Palette RAM index of the background at column `x`, 0 is the backdrop
+    fn background_pixel(&self, x: usize) -> u8 {
+        if !self.mask.contains(MaskRegister::SHOW_BACKGROUND)
+            || (x < 8 && !self.mask.contains(MaskRegister::SHOW_BACKGROUND_LEFT))
+        {
+            return 0;
+        }
+
+        let pipeline = &self.pipeline;
+        let bit = |register: u16| ((register << self.internal.x) >> 15) as u8 & 1;
+        let pixel = bit(pipeline.shift_pattern_hi) << 1 | bit(pipeline.shift_pattern_lo);
+        let palette = bit(pipeline.shift_attribute_hi) << 1 | bit(pipeline.shift_attribute_lo);
+
+        if pixel == 0 {
+            0
+        } else {
+            palette * 4 + pixel
+        }
+    }
+
+    /// Palette RAM index of the first opaque sprite at column `x`, along with the sprite
+    fn sprite_pixel(&self, x: usize) -> Option<(u8, ScanlineSprite)> {
+        if !self.mask.contains(MaskRegister::SHOW_SPRITES)
+            || (x < 8 && !self.mask.contains(MaskRegister::SHOW_SPRITES_LEFT))
+        {
+            return None;
+        }
+
+        self.pipeline
+            .sprites
+            .iter()
+            .find_map(|sprite| match sprite.pixel(x) {
+                0 => None,
+                pixel => Some((0x10 + sprite.attributes.palette() * 4 + pixel, *sprite)),
+            })
+    }
+
+    /// Color of palette RAM entry `index` through the system palette
+    fn palette_color(&self, index: u8) -> (u8, u8, u8) {
+        let mut color = self.peek_vram(0x3F00 + index as u16) & 0x3F;
+        if self.mask.contains(MaskRegister::GREYSCALE) {
+            color &= 0x30;
+        }
+        SYSTEM_PALETTE[color as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::{new_mapper, tests::test_rom, CHR_ROM_PAGE_SIZE};
+
+    use super::*;
+
+    const BACKDROP: u8 = 0x21;
+    const COLOR: u8 = 0x16;
+    const SPRITE_COLOR: u8 = 0x2A;
+    const SPRITE_COLOR_2: u8 = 0x27;
+
+    /// Tile 1 has its top row filled with color 1, tile 2 its left column and tile 0 is blank.
+    /// The tile at column 1, row 0 of the first nametable is tile 1 with palette 1.
+    fn test_ppu() -> PPU {
+        let mut rom = test_rom(&[]);
+        rom.chr_rom = vec![0; CHR_ROM_PAGE_SIZE];
+        rom.chr_rom[16] = 0xFF;
+        rom.chr_rom[32..40].fill(0x80);
+        // Second pattern table, for 8x16 sprites: bottom half of tiles 2/3 has its top row filled
+        rom.chr_rom[0x1000 + 3 * 16] = 0xFF;
+
+        let mut ppu = PPU::new(new_mapper(rom).unwrap());
+        ppu.vram[1] = 1;
+        ppu.vram[0x3C0] = 0b01;
+        ppu.palette_table[0] = BACKDROP;
+        ppu.palette_table[5] = COLOR;
+        ppu.palette_table[0x11] = SPRITE_COLOR;
+        ppu.palette_table[0x15] = SPRITE_COLOR_2;
+        ppu.mask
+            .update((MaskRegister::SHOW_BACKGROUND | MaskRegister::SHOW_BACKGROUND_LEFT).bits());
+        ppu
+    }
+
+    fn sprite_ppu(sprites: &[[u8; 4]]) -> PPU {
+        let mut ppu = test_ppu();
+        ppu.mask
+            .insert(MaskRegister::SHOW_SPRITES | MaskRegister::SHOW_SPRITES_LEFT);
+        // Move unused sprites below the screen
+        ppu.oam_data.fill(0xFF);
+        for (i, sprite) in sprites.iter().enumerate() {
+            ppu.oam_data[i * 4..i * 4 + 4].copy_from_slice(sprite);
+        }
+        ppu
+    }
+
+    /// Run the PPU through the next pre-render line and visible part of the frame
+    fn render_frame(ppu: &mut PPU) {
+        tick_to(ppu, 261, 0);
+        tick_to(ppu, 240, 0);
+    }
+
+    fn color_at(ppu: &mut PPU, x: usize, y: usize) -> u8 {
+        render_frame(ppu);
+        let rgb = ppu.frame.pixel(x, y);
+        SYSTEM_PALETTE
+            .iter()
+            .position(|color| *color == rgb)
+            .unwrap() as u8
+    }
+
+    #[test]
+    fn background() {
+        let mut ppu = test_ppu();
+        render_frame(&mut ppu);
+
+        assert_eq!(ppu.frame.pixel(0, 0), SYSTEM_PALETTE[BACKDROP as usize]);
+        assert_eq!(ppu.frame.pixel(8, 0), SYSTEM_PALETTE[COLOR as usize]);
+        assert_eq!(ppu.frame.pixel(15, 0), SYSTEM_PALETTE[COLOR as usize]);
+        assert_eq!(ppu.frame.pixel(16, 0), SYSTEM_PALETTE[BACKDROP as usize]);
+        assert_eq!(ppu.frame.pixel(8, 1), SYSTEM_PALETTE[BACKDROP as usize]);
+    }
+
+    #[test]
+    fn scroll() {
+        let mut ppu = test_ppu();
+        ppu.write_to_scroll(4);
+        ppu.write_to_scroll(0);
+        render_frame(&mut ppu);
+
+        assert_eq!(ppu.frame.pixel(3, 0), SYSTEM_PALETTE[BACKDROP as usize]);
+        assert_eq!(ppu.frame.pixel(4, 0), SYSTEM_PALETTE[COLOR as usize]);
+        assert_eq!(ppu.frame.pixel(12, 0), SYSTEM_PALETTE[BACKDROP as usize]);
+    }
+
+    #[test]
+    fn scroll_into_next_nametable() {
+        let mut ppu = test_ppu();
+        // Vertical mirroring: $2400 is the second nametable in VRAM
+        ppu.vram[0x400] = 1;
+        ppu.vram[0x400 + 0x3C0] = 0b01;
+        ppu.write_to_scroll(0xF8);
+        ppu.write_to_scroll(0);
+        render_frame(&mut ppu);
+
+        assert_eq!(ppu.frame.pixel(7, 0), SYSTEM_PALETTE[BACKDROP as usize]);
+        assert_eq!(ppu.frame.pixel(8, 0), SYSTEM_PALETTE[COLOR as usize]);
+    }
+
+    #[test]
+    fn hide_left_column() {
+        let mut ppu = test_ppu();
+        ppu.vram[0] = 1;
+        ppu.mask.update(MaskRegister::SHOW_BACKGROUND.bits());
+        render_frame(&mut ppu);
+
+        assert_eq!(ppu.frame.pixel(0, 0), SYSTEM_PALETTE[BACKDROP as usize]);
+        assert_eq!(ppu.frame.pixel(8, 0), SYSTEM_PALETTE[COLOR as usize]);
+    }
+
+    #[test]
+    fn greyscale() {
+        let mut ppu = test_ppu();
+        ppu.mask.insert(MaskRegister::GREYSCALE);
+        render_frame(&mut ppu);
+
+        assert_eq!(
+            ppu.frame.pixel(8, 0),
+            SYSTEM_PALETTE[(COLOR & 0x30) as usize]
+        );
+    }
+
+    fn tick_to(ppu: &mut PPU, scanline: u16, dot: usize) {
+        while (ppu.scanline, ppu.cycles) != (scanline, dot) {
+            ppu.tick(1);
+        }
+    }
+
+    #[test]
+    fn renders_while_ticking() {
+        let mut ppu = test_ppu();
+
+        while !ppu.tick(1) {}
+        tick_to(&mut ppu, 1, 0);
+        assert_eq!(ppu.frame.pixel(8, 0), SYSTEM_PALETTE[COLOR as usize]);
+    }
+
+    #[test]
+    fn sprite() {
+        let mut ppu = sprite_ppu(&[[9, 2, 0, 20]]);
+
+        assert_eq!(color_at(&mut ppu, 20, 9), BACKDROP);
+        assert_eq!(color_at(&mut ppu, 20, 10), SPRITE_COLOR);
+        assert_eq!(color_at(&mut ppu, 21, 10), BACKDROP);
+        assert_eq!(color_at(&mut ppu, 20, 17), SPRITE_COLOR);
+        assert_eq!(color_at(&mut ppu, 20, 18), BACKDROP);
+    }
+
+    #[test]
+    fn sprite_palette() {
+        let mut ppu = sprite_ppu(&[[9, 2, 0b01, 20]]);
+
+        assert_eq!(color_at(&mut ppu, 20, 10), SPRITE_COLOR_2);
+    }
+
+    #[test]
+    fn sprite_flip_horizontally() {
+        let mut ppu = sprite_ppu(&[[9, 2, 0b0100_0000, 20]]);
+
+        assert_eq!(color_at(&mut ppu, 20, 10), BACKDROP);
+        assert_eq!(color_at(&mut ppu, 27, 10), SPRITE_COLOR);
+    }
+
+    #[test]
+    fn sprite_flip_vertically() {
+        let mut ppu = sprite_ppu(&[[9, 1, 0b1000_0000, 20]]);
+
+        assert_eq!(color_at(&mut ppu, 20, 10), BACKDROP);
+        assert_eq!(color_at(&mut ppu, 20, 17), SPRITE_COLOR);
+        assert_eq!(color_at(&mut ppu, 27, 17), SPRITE_COLOR);
+    }
+
+    #[test]
+    fn sprite_priority() {
+        // Background tile 1 at column 1, row 1
+        let mut ppu = sprite_ppu(&[[7, 1, 0, 8], [7, 1, 0b0010_0000, 12]]);
+        ppu.vram[33] = 1;
+
+        assert_eq!(color_at(&mut ppu, 8, 8), SPRITE_COLOR);
+        // The first sprite in OAM wins, even against a sprite in front of the background
+        assert_eq!(color_at(&mut ppu, 12, 8), SPRITE_COLOR);
+        ppu.oam_data[..4].fill(0xFF);
+        assert_eq!(color_at(&mut ppu, 12, 8), COLOR);
+        assert_eq!(color_at(&mut ppu, 16, 8), SPRITE_COLOR);
+    }
+
+    #[test]
+    fn sprites_per_scanline() {
+        let sprites = (0..9).map(|i| [9, 2, 0, i * 8]).collect::<Vec<_>>();
+        let mut ppu = sprite_ppu(&sprites);
+
+        assert_eq!(color_at(&mut ppu, 56, 10), SPRITE_COLOR);
+        assert_eq!(color_at(&mut ppu, 64, 10), BACKDROP);
+    }
+
+    #[test]
+    fn sprite_8x16() {
+        let mut ppu = sprite_ppu(&[[9, 3, 0, 20]]);
+        ppu.ctrl.insert(ControlRegister::SPRITE_SIZE);
+
+        assert_eq!(color_at(&mut ppu, 20, 10), BACKDROP);
+        assert_eq!(color_at(&mut ppu, 20, 18), SPRITE_COLOR);
+        assert_eq!(color_at(&mut ppu, 27, 18), SPRITE_COLOR);
+        assert_eq!(color_at(&mut ppu, 20, 25), BACKDROP);
+        assert_eq!(color_at(&mut ppu, 20, 26), BACKDROP);
+
+        ppu.oam_data[2] = 0b1000_0000;
+        assert_eq!(color_at(&mut ppu, 20, 18), BACKDROP);
+        assert_eq!(color_at(&mut ppu, 20, 17), SPRITE_COLOR);
+    }
+
+    #[test]
+    fn sprite_pattern_table() {
+        let mut ppu = sprite_ppu(&[[9, 3, 0, 20]]);
+        ppu.ctrl
+            .insert(ControlRegister::SPRITE_PATTERN_TABLE_ADDRESS);
+
+        assert_eq!(color_at(&mut ppu, 20, 10), SPRITE_COLOR);
+        assert_eq!(color_at(&mut ppu, 20, 11), BACKDROP);
+    }
+
+    #[test]
+    fn hide_sprites_left_column() {
+        let mut ppu = sprite_ppu(&[[9, 1, 0, 4]]);
+        ppu.mask.remove(MaskRegister::SHOW_SPRITES_LEFT);
+
+        assert_eq!(color_at(&mut ppu, 7, 10), BACKDROP);
+        assert_eq!(color_at(&mut ppu, 8, 10), SPRITE_COLOR);
+    }
+
+    fn sprite_zero_ppu(x: u8, column: usize) -> PPU {
+        // Background tile 1 at row 1 and sprite 0 both have their top row opaque on scanline 8
+        let mut ppu = sprite_ppu(&[[7, 1, 0, x]]);
+        ppu.vram[32 + column] = 1;
+        ppu
+    }
+
+    #[test]
+    fn sprite_zero_hit() {
+        let mut ppu = sprite_zero_ppu(12, 1);
+
+        tick_to(&mut ppu, 8, 12);
+        assert!(!ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+        tick_to(&mut ppu, 8, 13);
+        assert!(ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+
+        tick_to(&mut ppu, 261, 0);
+        assert!(ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+        tick_to(&mut ppu, 261, 1);
+        assert!(!ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+    }
+
+    #[test]
+    fn sprite_zero_hit_behind_background() {
+        let mut ppu = sprite_zero_ppu(12, 1);
+        ppu.oam_data[2] = SpriteAttributes::BEHIND_BACKGROUND.bits();
+
+        tick_to(&mut ppu, 9, 0);
+        assert!(ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+    }
+
+    #[test]
+    fn no_sprite_zero_hit_on_last_column() {
+        let mut ppu = sprite_zero_ppu(255, 31);
+
+        tick_to(&mut ppu, 9, 0);
+        assert!(!ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+    }
+
+    #[test_case(MaskRegister::SHOW_BACKGROUND_LEFT ; "background")]
+    #[test_case(MaskRegister::SHOW_SPRITES_LEFT ; "sprites")]
+    fn no_sprite_zero_hit_when_clipped(clip: MaskRegister) {
+        let mut ppu = sprite_zero_ppu(4, 0);
+        ppu.vram[33] = 1;
+        ppu.mask.remove(clip);
+
+        tick_to(&mut ppu, 8, 8);
+        assert!(!ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+        tick_to(&mut ppu, 8, 9);
+        assert!(ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+    }
+
+    #[test]
+    fn no_sprite_zero_hit_with_another_sprite() {
+        let mut ppu = sprite_zero_ppu(12, 1);
+        ppu.oam_data[..8].copy_from_slice(&[0xFF, 0, 0, 0, 7, 1, 0, 12]);
+
+        tick_to(&mut ppu, 9, 0);
+        assert!(!ppu.status.contains(StatusRegister::SPRITE_ZERO_HIT));
+    }
+
+    fn overflow(sprites: &[[u8; 4]]) -> bool {
+        let mut ppu = sprite_ppu(sprites);
+        tick_to(&mut ppu, 11, 0);
+        ppu.status.contains(StatusRegister::SPRITE_OVERFLOW)
+    }
+
+    #[test]
+    fn sprite_overflow() {
+        let mut sprites = vec![[10, 0, 0, 0]; 8];
+        assert!(!overflow(&sprites));
+
+        sprites.push([10, 0, 0, 0]);
+        assert!(overflow(&sprites));
+    }
+
+    #[test]
+    fn sprite_overflow_cleared_on_pre_render_line() {
+        let mut ppu = sprite_ppu(&[[10, 0, 0, 0]; 9]);
+
+        tick_to(&mut ppu, 261, 0);
+        assert!(ppu.status.contains(StatusRegister::SPRITE_OVERFLOW));
+        tick_to(&mut ppu, 261, 1);
+        assert!(!ppu.status.contains(StatusRegister::SPRITE_OVERFLOW));
+    }
+
+    #[test]
+    fn sprite_overflow_false_positive() {
+        // The ninth sprite is off the line but its tile byte is read as the Y coordinate
+        let mut sprites = vec![[10, 0, 0, 0]; 8];
+        sprites.push([0xF0, 0xF0, 0, 0]);
+        sprites.push([0xF0, 10, 0, 0]);
+        assert!(overflow(&sprites));
+    }
+
+    #[test]
+    fn sprite_overflow_false_negative() {
+        // The tenth sprite is on the line but its tile byte is read as the Y coordinate
+        let mut sprites = vec![[10, 0, 0, 0]; 8];
+        sprites.push([0xF0, 0xF0, 0, 0]);
+        sprites.push([10, 0xF0, 0, 0]);
+        assert!(!overflow(&sprites));
+    }
+
+    #[test]
+    fn horizontal_scroll_reloaded_at_dot_257() {
+        // Tile 2 at column 1, row 1: a vertical line at x = 8 on scanlines 8 to 15
+        let mut ppu = test_ppu();
+        ppu.vram[33] = 2;
+        ppu.palette_table[1] = COLOR;
+
+        tick_to(&mut ppu, 9, 200);
+        ppu.write_to_scroll(8);
+        ppu.write_to_scroll(0);
+        tick_to(&mut ppu, 10, 300);
+        ppu.write_to_scroll(0);
+        ppu.write_to_scroll(0);
+        tick_to(&mut ppu, 13, 0);
+
+        assert_eq!(ppu.frame.pixel(8, 9), SYSTEM_PALETTE[COLOR as usize]);
+        assert_eq!(ppu.frame.pixel(0, 10), SYSTEM_PALETTE[COLOR as usize]);
+        assert_eq!(ppu.frame.pixel(0, 11), SYSTEM_PALETTE[COLOR as usize]);
+        assert_eq!(ppu.frame.pixel(8, 12), SYSTEM_PALETTE[COLOR as usize]);
+    }
+
+    #[test]
+    fn mid_scanline_mask_write() {
+        let mut ppu = test_ppu();
+        ppu.vram[..32].fill(1);
+        ppu.vram[0x3C0..0x3C8].fill(0b0101_0101);
+
+        tick_to(&mut ppu, 261, 0);
+        tick_to(&mut ppu, 0, 100);
+        ppu.write_to_mask(0);
+        tick_to(&mut ppu, 1, 0);
+
+        assert_eq!(ppu.frame.pixel(99, 0), SYSTEM_PALETTE[COLOR as usize]);
+        assert_eq!(ppu.frame.pixel(100, 0), SYSTEM_PALETTE[BACKDROP as usize]);
+    }
+
+    fn frame_length(ppu: &mut PPU) -> usize {
+        let mut dots = 1;
+        while !ppu.tick(1) {
+            dots += 1;
+        }
+        dots
+    }
+
+    #[test]
+    fn odd_frame_skips_a_dot() {
+        let mut ppu = test_ppu();
+        frame_length(&mut ppu);
+
+        let lengths = [frame_length(&mut ppu), frame_length(&mut ppu)];
+        assert_eq!(lengths.iter().sum::<usize>(), 341 * 262 * 2 - 1);
+        assert_eq!(lengths.iter().min(), Some(&(341 * 262 - 1)));
+
+        ppu.write_to_mask(0);
+        assert_eq!(frame_length(&mut ppu), 341 * 262);
+        assert_eq!(frame_length(&mut ppu), 341 * 262);
+    }
+}
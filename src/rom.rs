@@ -4,12 +4,53 @@ pub const PRG_ROM_PAGE_SIZE: usize = 16384;
 pub const CHR_ROM_PAGE_SIZE: usize = 8192;
 pub const PRG_RAM_PAGE_SIZE: usize = 8192;
 pub const TRAINER_SIZE: usize = 512;
+/// Where the trainer is loaded in PRG RAM
+pub const TRAINER: u16 = 0x7000;
+
+use crate::Nametable;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Mirroring {
     Vertical,
     Horizontal,
     FourScreen,
+    /// Every nametable maps to the first 1 KB of VRAM
+    SingleScreenA,
+    /// Every nametable maps to the second 1 KB of VRAM
+    SingleScreenB,
+}
+
+impl Mirroring {
+    // Horizontal:
+    //   [ A ] [ a ]
+    //   [ B ] [ b ]
+    // Vertical:
+    //   [ A ] [ B ]
+    //   [ a ] [ b ]
+    // Single Screen:
+    //   [ A ] [ a ]
+    //   [ a ] [ a ]
+    // Four Screen:
+    //   [ A ] [ B ]
+    //   [ C ] [ D ]  <- cartridge VRAM
+    /// Where the nametable access at `addr` ($2000-$3EFF) lands with this wiring
+    pub fn nametable(self, addr: u16) -> Nametable {
+        // $3000-$3EFF mirrors $2000-$2EFF
+        let index = addr & 0x0FFF;
+        let name_table = index / 0x400;
+        let offset = index % 0x400;
+
+        let page = match (self, name_table) {
+            (Mirroring::Horizontal, _) => name_table / 2,
+            (Mirroring::Vertical, _) => name_table % 2,
+            (Mirroring::SingleScreenA, _) => 0,
+            (Mirroring::SingleScreenB, _) => 1,
+            (Mirroring::FourScreen, 0 | 1) => name_table,
+            (Mirroring::FourScreen, _) => return Nametable::Cartridge,
+        };
+
+        Nametable::Console(page * 0x400 + offset)
+    }
 }
 
 #[derive(Debug)]
@@ -33,6 +74,8 @@ pub struct RawROM<'a> {
     tv_system_prg_ram: u8,
     /// Unused padding (should be filled with zero, but some rippers put their name across bytes 7-15)
     zeroes: &'a [u8],
+    /// Bytes 8-15 reinterpreted when flags 7 identifies a NES 2.0 header
+    nes_2_0: Option<Nes2Header>,
     program: &'a [u8],
 }
 
@@ -42,27 +85,83 @@ impl<'a> RawROM<'a> {
             return Err(RomError::TooShort);
         }
 
+        let flags_7 = Flags7::new(bytes[7])?;
+        let nes_2_0 = match flags_7.format {
+            FormatOption::One => None,
+            FormatOption::Two => Some(Nes2Header::new(&bytes[8..16])),
+        };
+
         Ok(Self {
             tag: &bytes[0..4],
             prg_rom_size: bytes[4],
             chr_rom_size: bytes[5],
             flags_6: Flags6::new(bytes[6]),
-            flags_7: Flags7::new(bytes[7])?,
+            flags_7,
             prg_ram_len: bytes[8],
             tv_system: bytes[9],
             tv_system_prg_ram: bytes[10],
             zeroes: &bytes[11..16],
+            nes_2_0,
             program: &bytes[16..],
         })
     }
 }
 
+/// CPU/PPU timing the game was made for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    /// RP2C02, North America, Japan
+    NTSC,
+    /// RP2C07, Europe, Australia
+    PAL,
+    /// Runs on NTSC and PAL consoles
+    MultiRegion,
+    /// UA6538, Russia
+    Dendy,
+}
+
+/// Hardware the game runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleType {
+    NES,
+    /// Vs. System arcade, with the NES 2.0 PPU (0-15) and hardware (0-6) type numbers
+    VsSystem {
+        ppu: u8,
+        hardware: u8,
+    },
+    PlayChoice10,
+    /// NES 2.0 extended console type number
+    Extended(u8),
+}
+
 #[derive(Debug)]
 pub struct Rom {
     pub prg_rom: Vec<u8>,
     pub chr_rom: Vec<u8>,
-    pub mapper: u8,
+    /// Size of the CHR RAM the board carries instead of CHR ROM, 0 when it has CHR ROM
+    pub chr_ram_size: usize,
+    /// Size of the battery-backed CHR RAM
+    pub chr_nvram_size: usize,
+    /// Size of the volatile PRG RAM at $6000-$7FFF
+    pub prg_ram_size: usize,
+    /// Size of the battery-backed PRG RAM at $6000-$7FFF, kept between sessions
+    pub prg_nvram_size: usize,
+    /// Whether the board has a battery or other non-volatile memory
+    pub battery: bool,
+    /// 12-bit mapper number, only NES 2.0 headers use the upper 4 bits
+    pub mapper: u16,
+    /// Board variant of the mapper, 0 when unknown or with iNES 1.0 headers
+    pub submapper: u8,
     pub screen_mirroring: Mirroring,
+    pub timing: Timing,
+    pub console: ConsoleType,
+    /// Default expansion device as numbered by NES 2.0 (1 for standard controllers),
+    /// 0 when unspecified
+    pub expansion_device: u8,
+    /// Number of miscellaneous ROMs following CHR ROM in NES 2.0 files, 0 with iNES 1.0 headers
+    pub misc_roms: u8,
+    /// 512 bytes copied to $7000-$71FF on power-up, used by dumps of copier-hacked games
+    pub trainer: Option<Vec<u8>>,
 }
 
 impl Rom {
@@ -76,11 +175,8 @@ impl Rom {
             return Err(RomError::WrongTag);
         }
 
-        if raw.flags_7.format != FormatOption::One {
-            return Err(RomError::UnsupportedFormat);
-        }
-
-        let mapper = raw.flags_6.lower_mapper | raw.flags_7.upper_mapper << 4;
+        let flags_7 = &raw.flags_7;
+        let mapper = (raw.flags_6.lower_mapper | flags_7.upper_mapper << 4) as u16;
 
         let screen_mirroring = if raw.flags_6.four_screen {
             Mirroring::FourScreen
@@ -88,41 +184,153 @@ impl Rom {
             raw.flags_6.mirroring.into()
         };
 
-        let prg_rom_size = raw.prg_rom_size as usize * PRG_ROM_PAGE_SIZE;
-        let chr_rom_size = raw.chr_rom_size as usize * CHR_ROM_PAGE_SIZE;
+        let console = match (flags_7.play_choice_10, flags_7.vs_unisystem) {
+            (false, false) => ConsoleType::NES,
+            (false, true) => ConsoleType::VsSystem {
+                ppu: 0,
+                hardware: 0,
+            },
+            (true, false) => ConsoleType::PlayChoice10,
+            (true, true) => ConsoleType::Extended(0),
+        };
 
-        let prg_rom_start = if raw.flags_6.has_trainer {
-            TRAINER_SIZE
+        let mut rom = Self {
+            prg_rom: Vec::new(),
+            chr_rom: Vec::new(),
+            chr_ram_size: 0,
+            chr_nvram_size: 0,
+            prg_ram_size: 0,
+            prg_nvram_size: 0,
+            battery: raw.flags_6.has_battery_ram,
+            mapper,
+            submapper: 0,
+            screen_mirroring,
+            timing: Timing::NTSC,
+            console,
+            expansion_device: 0,
+            misc_roms: 0,
+            trainer: None,
+        };
+
+        let (prg_rom_size, chr_rom_size) = match &raw.nes_2_0 {
+            None => {
+                let prg_rom_size = raw.prg_rom_size as usize * PRG_ROM_PAGE_SIZE;
+                let chr_rom_size = raw.chr_rom_size as usize * CHR_ROM_PAGE_SIZE;
+                // iNES 1.0 boards without CHR ROM carry a single 8 KB CHR RAM
+                if chr_rom_size == 0 {
+                    rom.chr_ram_size = CHR_ROM_PAGE_SIZE;
+                }
+
+                // Byte 8 is only trusted when the end of the header is clean, rippers leave
+                // their names there. A size of 0 infers 8 KB for compatibility
+                let prg_ram_len = match raw.zeroes[1..] {
+                    [0, 0, 0, 0] => raw.prg_ram_len.max(1),
+                    _ => 1,
+                };
+                let prg_ram_size = prg_ram_len as usize * PRG_RAM_PAGE_SIZE;
+                if rom.battery {
+                    rom.prg_nvram_size = prg_ram_size;
+                } else {
+                    rom.prg_ram_size = prg_ram_size;
+                }
+
+                if raw.tv_system & 1 != 0 {
+                    rom.timing = Timing::PAL;
+                }
+
+                (prg_rom_size, chr_rom_size)
+            }
+            Some(header) => {
+                rom.mapper |= (header.upper_mapper as u16) << 8;
+                rom.submapper = header.submapper;
+                rom.prg_ram_size = header.prg_ram_size;
+                rom.prg_nvram_size = header.prg_nvram_size;
+                rom.chr_ram_size = header.chr_ram_size;
+                rom.chr_nvram_size = header.chr_nvram_size;
+                rom.timing = header.timing;
+                rom.expansion_device = header.expansion_device;
+                rom.misc_roms = header.misc_roms;
+                rom.console = match rom.console {
+                    ConsoleType::VsSystem { .. } => ConsoleType::VsSystem {
+                        ppu: header.console_type & 0b1111,
+                        hardware: header.console_type >> 4,
+                    },
+                    ConsoleType::Extended(_) => ConsoleType::Extended(header.console_type & 0b1111),
+                    console => console,
+                };
+
+                (
+                    rom_size(raw.prg_rom_size, header.prg_rom_size_msb, PRG_ROM_PAGE_SIZE)?,
+                    rom_size(raw.chr_rom_size, header.chr_rom_size_msb, CHR_ROM_PAGE_SIZE)?,
+                )
+            }
+        };
+
+        let trainer = if raw.flags_6.has_trainer {
+            let trainer = raw
+                .program
+                .get(..TRAINER_SIZE)
+                .ok_or(RomError::TrainerOutOfBounds {
+                    len: raw.program.len(),
+                })?;
+            Some(trainer.to_vec())
         } else {
-            0
+            None
         };
-        let chr_rom_start = prg_rom_start + prg_rom_size;
+
+        let prg_rom_start = if trainer.is_some() { TRAINER_SIZE } else { 0 };
+        let chr_rom_start = prg_rom_start
+            .checked_add(prg_rom_size)
+            .ok_or(RomError::SizeOverflow)?;
+        let chr_rom_end = chr_rom_start
+            .checked_add(chr_rom_size)
+            .ok_or(RomError::SizeOverflow)?;
 
         let prg_rom = raw
             .program
-            .get(prg_rom_start..(prg_rom_start + prg_rom_size))
-            .ok_or_else(|| RomError::PrgRomOutOfBounds {
+            .get(prg_rom_start..chr_rom_start)
+            .ok_or(RomError::PrgRomOutOfBounds {
                 len: raw.program.len(),
                 start: prg_rom_start,
-                end: prg_rom_start + prg_rom_size,
+                end: chr_rom_start,
             })?
             .to_vec();
         let chr_rom = raw
             .program
-            .get(chr_rom_start..(chr_rom_start + chr_rom_size))
-            .ok_or_else(|| RomError::ChrRomOutOfBounds {
+            .get(chr_rom_start..chr_rom_end)
+            .ok_or(RomError::ChrRomOutOfBounds {
                 len: raw.program.len(),
                 start: chr_rom_start,
-                end: chr_rom_start + chr_rom_size,
+                end: chr_rom_end,
             })?
             .to_vec();
 
-        Ok(Self {
-            prg_rom,
-            chr_rom,
-            mapper,
-            screen_mirroring,
-        })
+        rom.prg_rom = prg_rom;
+        rom.chr_rom = chr_rom;
+        rom.trainer = trainer;
+        Ok(rom)
+    }
+}
+
+/// ROM size from a NES 2.0 LSB and MSB nibble, in `unit` bytes or as an exponent-multiplier
+/// (LSB: EEEE EEMM, 2^E * (MM * 2 + 1) bytes) when the MSB nibble is $F
+fn rom_size(lsb: u8, msb: u8, unit: usize) -> Result<usize, RomError> {
+    if msb == 0xF {
+        let multiplier = (lsb & 0b11) as usize * 2 + 1;
+        1usize
+            .checked_shl((lsb >> 2) as u32)
+            .and_then(|size| size.checked_mul(multiplier))
+            .ok_or(RomError::SizeOverflow)
+    } else {
+        Ok((((msb as usize) << 8) | lsb as usize) * unit)
+    }
+}
+
+/// RAM size from a NES 2.0 shift count, 64 << shift bytes or none
+fn ram_size(shift: u8) -> usize {
+    match shift {
+        0 => 0,
+        shift => 64 << shift,
     }
 }
 
@@ -134,8 +342,10 @@ pub enum RomError {
     WrongTag,
     #[error("Unknown format")]
     UnknownFormat,
-    #[error("NES2.0 format is not supported")]
-    UnsupportedFormat,
+    #[error("The ROM size does not fit in memory")]
+    SizeOverflow,
+    #[error("trainer 0..{} on program of len {len}", TRAINER_SIZE)]
+    TrainerOutOfBounds { len: usize },
     #[error("prg_rom {start}..{end} on program of len {len}")]
     PrgRomOutOfBounds {
         len: usize,
@@ -202,6 +412,57 @@ impl Flags7 {
     }
 }
 
+/// Bytes 8-15 of a NES 2.0 header
+#[derive(Debug)]
+pub struct Nes2Header {
+    /// Byte 8 – Mapper bits 8-11
+    upper_mapper: u8,
+    /// Byte 8 – Submapper
+    submapper: u8,
+    /// Byte 9 – PRG ROM size MSB, $F selects the exponent-multiplier notation
+    prg_rom_size_msb: u8,
+    /// Byte 9 – CHR ROM size MSB, $F selects the exponent-multiplier notation
+    chr_rom_size_msb: u8,
+    /// Byte 10 – PRG RAM and PRG NVRAM shift counts
+    prg_ram_size: usize,
+    prg_nvram_size: usize,
+    /// Byte 11 – CHR RAM and CHR NVRAM shift counts
+    chr_ram_size: usize,
+    chr_nvram_size: usize,
+    /// Byte 12 – CPU/PPU timing
+    timing: Timing,
+    /// Byte 13 – Vs. System PPU and hardware type, or extended console type
+    console_type: u8,
+    /// Byte 14 – Number of miscellaneous ROMs after CHR ROM
+    misc_roms: u8,
+    /// Byte 15 – Default expansion device
+    expansion_device: u8,
+}
+
+impl Nes2Header {
+    fn new(bytes: &[u8]) -> Self {
+        Self {
+            upper_mapper: bytes[0] & 0b1111,
+            submapper: bytes[0] >> 4,
+            prg_rom_size_msb: bytes[1] & 0b1111,
+            chr_rom_size_msb: bytes[1] >> 4,
+            prg_ram_size: ram_size(bytes[2] & 0b1111),
+            prg_nvram_size: ram_size(bytes[2] >> 4),
+            chr_ram_size: ram_size(bytes[3] & 0b1111),
+            chr_nvram_size: ram_size(bytes[3] >> 4),
+            timing: match bytes[4] & 0b11 {
+                0 => Timing::NTSC,
+                1 => Timing::PAL,
+                2 => Timing::MultiRegion,
+                _ => Timing::Dendy,
+            },
+            console_type: bytes[5],
+            misc_roms: bytes[6] & 0b11,
+            expansion_device: bytes[7] & 0b11_1111,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MirroringOption {
     Vertical,
@@ -225,6 +486,8 @@ pub enum FormatOption {
 
 #[cfg(test)]
 pub mod tests {
+    use test_case::test_case;
+
     use crate::{PROGRAM, PROGRAM_START};
 
     use super::*;
@@ -234,6 +497,10 @@ pub mod tests {
     const HEADER: [u8; HEADER_SIZE] = [
         0x4E, 0x45, 0x53, 0x1A, 0x02, 0x01, F6, F7, 0xE4, 0xC0, 0x22, 0x00, 0x00, 0x00, 0x00, 0x00,
     ];
+    /// NROM, vertical mirroring
+    const TEST_HEADER: [u8; HEADER_SIZE] = [
+        0x4E, 0x45, 0x53, 0x1A, 0x02, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
 
     pub fn test_rom(program: &[u8]) -> Rom {
         let mut prg_rom = program.to_vec();
@@ -243,7 +510,7 @@ pub mod tests {
         prg_rom[(PROGRAM_START - PROGRAM + 1) as usize] = hi;
 
         let rom = MockROM {
-            header: HEADER.to_vec(),
+            header: TEST_HEADER.to_vec(),
             trainer: None,
             prg_rom,
             chr_rom: vec![0; CHR_ROM_PAGE_SIZE],
@@ -252,6 +519,33 @@ pub mod tests {
         Rom::new(&rom.into_bytes()).unwrap()
     }
 
+    /// Every 8 KB of PRG ROM is filled with its 8 KB bank number
+    /// and every 1 KB of CHR ROM is filled with its 1 KB bank number,
+    /// so tests can tell which bank is mapped at a given address.
+    pub fn mapper_rom(mapper: u16, prg_rom_size: u8, chr_rom_size: u8) -> Rom {
+        let mut header = TEST_HEADER;
+        header[4] = prg_rom_size;
+        header[5] = chr_rom_size;
+        header[6] = (mapper as u8) << 4;
+        header[7] = mapper as u8 & 0b1111_0000;
+
+        let prg_rom = (0..prg_rom_size as usize * PRG_ROM_PAGE_SIZE)
+            .map(|i| (i / 0x2000) as u8)
+            .collect();
+        let chr_rom = (0..chr_rom_size as usize * CHR_ROM_PAGE_SIZE)
+            .map(|i| (i / 0x0400) as u8)
+            .collect();
+
+        let rom = MockROM {
+            header: header.to_vec(),
+            trainer: None,
+            prg_rom,
+            chr_rom,
+        };
+
+        Rom::new(&rom.into_bytes()).unwrap()
+    }
+
     #[test]
     fn header() {
         let bytes = HEADER
@@ -322,7 +616,166 @@ pub mod tests {
         let rom = Rom::new(&mock.into_bytes()).unwrap();
         assert_eq!(rom.prg_rom, prg_rom);
         assert_eq!(rom.chr_rom, chr_rom);
+        assert_eq!(rom.chr_ram_size, 0);
+        assert_eq!(rom.prg_ram_size, 0);
+        assert_eq!(rom.prg_nvram_size, 0xE4 * PRG_RAM_PAGE_SIZE);
+        assert!(rom.battery);
+        assert_eq!(rom.submapper, 0);
+        assert_eq!(rom.timing, Timing::NTSC);
+        assert_eq!(
+            rom.console,
+            ConsoleType::VsSystem {
+                ppu: 0,
+                hardware: 0
+            }
+        );
         assert_eq!(rom.mapper, 0b0110_1011);
         assert_eq!(rom.screen_mirroring, Mirroring::FourScreen);
     }
+
+    #[test]
+    fn nes_2_0() {
+        let header = [
+            0x4E,
+            0x45,
+            0x53,
+            0x1A,
+            0x02,
+            0x00,
+            0x00,
+            0b0000_1001,
+            0x21,
+            0x00,
+            0x70,
+            0x07,
+            0x01,
+            0x35,
+            0x01,
+            0x01,
+        ];
+        let prg_rom = vec![0x80; 0x02 * PRG_ROM_PAGE_SIZE];
+        let mock = MockROM {
+            header: header.to_vec(),
+            trainer: None,
+            prg_rom: prg_rom.clone(),
+            chr_rom: Vec::new(),
+        };
+
+        let rom = Rom::new(&mock.into_bytes()).unwrap();
+        assert_eq!(rom.prg_rom, prg_rom);
+        assert!(rom.chr_rom.is_empty());
+        assert_eq!(rom.mapper, 0x100);
+        assert_eq!(rom.submapper, 2);
+        assert_eq!(rom.prg_ram_size, 0);
+        assert_eq!(rom.prg_nvram_size, 0x2000);
+        assert_eq!(rom.chr_ram_size, 0x2000);
+        assert_eq!(rom.chr_nvram_size, 0);
+        assert_eq!(rom.timing, Timing::PAL);
+        assert_eq!(
+            rom.console,
+            ConsoleType::VsSystem {
+                ppu: 5,
+                hardware: 3
+            }
+        );
+        assert_eq!(rom.expansion_device, 1);
+        assert_eq!(rom.misc_roms, 1);
+    }
+
+    #[test_case(0x02, 0x0, 0x8000 ; "pages")]
+    #[test_case(0x00, 0x1, 0x40_0000 ; "msb")]
+    #[test_case(0b0000_1000, 0xF, 4 ; "exponent")]
+    #[test_case(0b0100_0001, 0xF, 0x3_0000 ; "exponent_multiplier")]
+    fn nes_2_0_rom_size(lsb: u8, msb: u8, size: usize) {
+        assert_eq!(rom_size(lsb, msb, PRG_ROM_PAGE_SIZE).unwrap(), size);
+    }
+
+    #[test]
+    fn nes_2_0_rom_size_overflow() {
+        assert!(matches!(
+            rom_size(0xFF, 0xF, PRG_ROM_PAGE_SIZE),
+            Err(RomError::SizeOverflow)
+        ));
+    }
+
+    #[test_case(0, [0; 4], PRG_RAM_PAGE_SIZE ; "unspecified")]
+    #[test_case(4, [0; 4], 4 * PRG_RAM_PAGE_SIZE ; "specified")]
+    #[test_case(4, *b"Dude", PRG_RAM_PAGE_SIZE ; "junk")]
+    fn ines_prg_ram_size(len: u8, tail: [u8; 4], size: usize) {
+        let mut header = TEST_HEADER;
+        header[8] = len;
+        header[12..].copy_from_slice(&tail);
+        let mock = MockROM {
+            header: header.to_vec(),
+            trainer: None,
+            prg_rom: vec![0; 0x02 * PRG_ROM_PAGE_SIZE],
+            chr_rom: vec![0; CHR_ROM_PAGE_SIZE],
+        };
+
+        let rom = Rom::new(&mock.into_bytes()).unwrap();
+        assert_eq!(rom.prg_ram_size, size);
+    }
+
+    #[test]
+    fn trainer() {
+        let mut header = TEST_HEADER;
+        header[6] |= 0b100;
+        let trainer = (0..TRAINER_SIZE).map(|i| i as u8).collect::<Vec<_>>();
+        let prg_rom = vec![0x80; 0x02 * PRG_ROM_PAGE_SIZE];
+        let mock = MockROM {
+            header: header.to_vec(),
+            trainer: Some(trainer.clone()),
+            prg_rom: prg_rom.clone(),
+            chr_rom: vec![0; CHR_ROM_PAGE_SIZE],
+        };
+
+        let rom = Rom::new(&mock.into_bytes()).unwrap();
+        assert_eq!(rom.trainer, Some(trainer));
+        assert_eq!(rom.prg_rom, prg_rom);
+    }
+
+    #[test]
+    fn trainer_out_of_bounds() {
+        let mut header = TEST_HEADER;
+        header[6] |= 0b100;
+        let mock = MockROM {
+            header: header.to_vec(),
+            trainer: Some(vec![0; 0x100]),
+            prg_rom: Vec::new(),
+            chr_rom: Vec::new(),
+        };
+
+        assert!(matches!(
+            Rom::new(&mock.into_bytes()),
+            Err(RomError::TrainerOutOfBounds { len: 0x100 })
+        ));
+    }
+
+    #[test]
+    fn chr_ram() {
+        let rom = mapper_rom(0, 2, 0);
+
+        assert!(rom.chr_rom.is_empty());
+        assert_eq!(rom.chr_ram_size, CHR_ROM_PAGE_SIZE);
+    }
+
+    #[test_case(Mirroring::Horizontal, [0x000, 0x000, 0x400, 0x400] ; "horizontal")]
+    #[test_case(Mirroring::Vertical, [0x000, 0x400, 0x000, 0x400] ; "vertical")]
+    #[test_case(Mirroring::SingleScreenA, [0x000; 4] ; "single_screen_a")]
+    #[test_case(Mirroring::SingleScreenB, [0x400; 4] ; "single_screen_b")]
+    fn nametable(mirroring: Mirroring, pages: [u16; 4]) {
+        for (addr, page) in [0x2005, 0x2405, 0x2805, 0x3C05].into_iter().zip(pages) {
+            assert_eq!(mirroring.nametable(addr), Nametable::Console(page + 5));
+        }
+    }
+
+    #[test]
+    fn four_screen_nametable() {
+        let mirroring = Mirroring::FourScreen;
+
+        assert_eq!(mirroring.nametable(0x2005), Nametable::Console(0x005));
+        assert_eq!(mirroring.nametable(0x3405), Nametable::Console(0x405));
+        assert_eq!(mirroring.nametable(0x2805), Nametable::Cartridge);
+        assert_eq!(mirroring.nametable(0x2C05), Nametable::Cartridge);
+    }
 }
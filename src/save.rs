@@ -0,0 +1,116 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::SharedMapper;
+
+/// Battery-backed PRG RAM persisted in a `.sav` file next to the ROM.
+///
+/// The file is loaded when opened and written back by `flush`, and when dropped
+/// (where errors can't be reported, call `flush` to handle them).
+#[derive(Debug)]
+pub struct SaveFile {
+    path: PathBuf,
+    mapper: SharedMapper,
+}
+
+impl SaveFile {
+    /// Attach the save file of the ROM at `rom_path` to the cartridge, loading it if it exists.
+    /// Returns `None` when the cartridge has no battery
+    pub fn open(rom_path: impl AsRef<Path>, mapper: SharedMapper) -> io::Result<Option<Self>> {
+        if mapper.borrow().battery_ram().is_empty() {
+            return Ok(None);
+        }
+
+        let path = rom_path.as_ref().with_extension("sav");
+        match fs::read(&path) {
+            Ok(data) => {
+                let mut mapper = mapper.borrow_mut();
+                let ram = mapper.battery_ram_mut();
+                let len = ram.len().min(data.len());
+                ram[..len].copy_from_slice(&data[..len]);
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        Ok(Some(Self { path, mapper }))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the battery RAM to disk
+    pub fn flush(&self) -> io::Result<()> {
+        fs::write(&self.path, self.mapper.borrow().battery_ram())
+    }
+}
+
+impl Drop for SaveFile {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{new_mapper, tests::test_rom, PRG_RAM_PAGE_SIZE};
+
+    use super::*;
+
+    fn battery_mapper() -> SharedMapper {
+        let mut rom = test_rom(&[]);
+        rom.battery = true;
+        rom.prg_nvram_size = rom.prg_ram_size;
+        rom.prg_ram_size = 0;
+        new_mapper(rom).unwrap()
+    }
+
+    fn rom_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nes-emulator-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let rom_path = rom_path("saves_and_loads.nes");
+        let mapper = battery_mapper();
+        let save = SaveFile::open(&rom_path, mapper.clone()).unwrap().unwrap();
+        assert_eq!(save.path(), rom_path.with_extension("sav"));
+
+        mapper.borrow_mut().cpu_write(0x6000, 0x42);
+        save.flush().unwrap();
+        let data = fs::read(save.path()).unwrap();
+        assert_eq!(data.len(), PRG_RAM_PAGE_SIZE);
+        assert_eq!(data[0], 0x42);
+
+        let mapper = battery_mapper();
+        let _save = SaveFile::open(&rom_path, mapper.clone()).unwrap().unwrap();
+        assert_eq!(mapper.borrow_mut().cpu_read(0x6000), 0x42);
+    }
+
+    #[test]
+    fn flushes_on_drop() {
+        let rom_path = rom_path("flushes_on_drop.nes");
+        let mapper = battery_mapper();
+
+        let save = SaveFile::open(&rom_path, mapper.clone()).unwrap().unwrap();
+        mapper.borrow_mut().cpu_write(0x7FFF, 0x24);
+        drop(save);
+
+        let data = fs::read(rom_path.with_extension("sav")).unwrap();
+        assert_eq!(data[PRG_RAM_PAGE_SIZE - 1], 0x24);
+    }
+
+    #[test]
+    fn no_battery() {
+        let rom_path = rom_path("no_battery.nes");
+        let mapper = new_mapper(test_rom(&[])).unwrap();
+
+        assert!(SaveFile::open(&rom_path, mapper).unwrap().is_none());
+        assert!(!rom_path.with_extension("sav").exists());
+    }
+}
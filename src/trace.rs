@@ -97,26 +97,25 @@ pub enum InstructionTrace {
 }
 
 impl InstructionTrace {
-    pub fn new(cpu: &mut CPU) -> Self {
-        let mode = cpu.get_addressing_mode();
+    pub fn new(cpu: &CPU) -> Self {
+        let code = cpu.peek(cpu.program_counter);
+        let mode = AddressingMode::new(code);
 
-        let (addr, value) = match mode {
+        let address = match mode {
             AddressingMode::Implied => return Self::Implied,
             AddressingMode::Accumulator => return Self::Accumulator,
-            _ => {
-                let addr = cpu.get_operand_address().0;
-                (addr, cpu.mem_read(addr))
-            }
+            _ => match mode.bytes() {
+                2 => cpu.peek(cpu.program_counter + 1) as u16,
+                3 => cpu.peek_u16(cpu.program_counter + 1),
+                _ => unreachable!("already returned from accumulator and implied"),
+            },
         };
 
-        let address = match mode.bytes() {
-            2 => cpu.mem_read(cpu.program_counter + 1) as u16,
-            3 => cpu.mem_read_u16(cpu.program_counter + 1),
-            _ => unreachable!("already returned from accumulator and implied"),
-        };
+        let addr = operand_address(cpu, mode, address);
+        let value = cpu.peek(addr);
 
         // Special cases
-        match cpu.mem_read(cpu.program_counter) {
+        match code {
             JMP_ABSOLUTE | JSR => return Self::Relative(address),
             _ => (),
         }
@@ -185,6 +184,41 @@ impl InstructionTrace {
     }
 }
 
+/// Effective address of an operand, computed like `CPU::get_operand_address`
+/// but peeking memory without its dummy reads
+fn operand_address(cpu: &CPU, mode: AddressingMode, operand: u16) -> u16 {
+    match mode {
+        AddressingMode::Immediate => cpu.program_counter + 1,
+        AddressingMode::ZeroPage | AddressingMode::Absolute => operand,
+        AddressingMode::ZeroPageX => (operand as u8).wrapping_add(cpu.register_x) as u16,
+        AddressingMode::ZeroPageY => (operand as u8).wrapping_add(cpu.register_y) as u16,
+        AddressingMode::AbsoluteX => operand.wrapping_add(cpu.register_x as u16),
+        AddressingMode::AbsoluteY => operand.wrapping_add(cpu.register_y as u16),
+        AddressingMode::Indirect => {
+            // Same page wrap bug as the CPU
+            let lo = cpu.peek(operand);
+            let hi = cpu.peek((operand & 0xFF00) | (operand.wrapping_add(1) & 0x00FF));
+            u16::from_le_bytes([lo, hi])
+        }
+        AddressingMode::IndirectX => {
+            let pos = (operand as u8).wrapping_add(cpu.register_x);
+            let lo = cpu.peek(pos as u16);
+            let hi = cpu.peek(pos.wrapping_add(1) as u16);
+            u16::from_le_bytes([lo, hi])
+        }
+        AddressingMode::IndirectY => {
+            let lo = cpu.peek(operand);
+            let hi = cpu.peek((operand as u8).wrapping_add(1) as u16);
+            u16::from_le_bytes([lo, hi]).wrapping_add(cpu.register_y as u16)
+        }
+        AddressingMode::Relative => cpu
+            .program_counter
+            .wrapping_add(2)
+            .wrapping_add_signed(operand as i8 as i16),
+        AddressingMode::Accumulator | AddressingMode::Implied => unreachable!(),
+    }
+}
+
 #[derive(Debug)]
 pub enum Register {
     X,